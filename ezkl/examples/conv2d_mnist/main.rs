@@ -4,38 +4,27 @@ use ezkl::circuit::polynomial::{
 };
 use ezkl::fieldutils;
 use ezkl::fieldutils::i32_to_felt;
+use ezkl::graph::utilities::vector_to_quantized;
+use ezkl::pfsys::ipa;
+use ezkl::tensor::ops::{affine, convolution, nonlinearities};
 use ezkl::tensor::*;
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Layouter, SimpleFloorPlanner, Value},
     plonk::{
-        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Column, ConstraintSystem, Error,
-        Instance,
-    },
-    poly::{
-        commitment::ParamsProver,
-        ipa::{
-            commitment::{IPACommitmentScheme, ParamsIPA},
-            multiopen::ProverIPA,
-            strategy::SingleStrategy,
-        },
-        VerificationStrategy,
-    },
-    transcript::{
-        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+        keygen_pk, keygen_vk, Circuit, Column, ConstraintSystem, Error, Instance, VerifyingKey,
     },
+    poly::{commitment::ParamsProver, ipa::commitment::ParamsIPA},
 };
 use halo2curves::pasta::vesta;
 use halo2curves::pasta::Fp as F;
 use mnist::*;
-use rand::rngs::OsRng;
 use std::cmp::max;
+use std::error::Error as StdError;
 use std::time::Instant;
 
 mod params;
 
-const K: usize = 17;
-
 #[derive(Clone)]
 struct Config<
     F: FieldExt + TensorType,
@@ -136,7 +125,12 @@ where
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        self.clone()
+        let unknown = |v: &ValTensor<F>| ValTensor::<F>::unknown(v.dims());
+        MyCircuit {
+            input: unknown(&self.input),
+            l0_params: [unknown(&self.l0_params[0]), unknown(&self.l0_params[1])],
+            l2_params: [unknown(&self.l2_params[0]), unknown(&self.l2_params[1])],
+        }
     }
 
     // Here we wire together the layers by using the output advice in each layer as input advice in the next (not with copying / equality).
@@ -147,7 +141,7 @@ where
 
         let input = VarTensor::new_advice(
             cs,
-            K,
+            Self::K,
             max(IN_CHANNELS * IMAGE_HEIGHT * IMAGE_WIDTH, LEN),
             vec![IN_CHANNELS, IMAGE_HEIGHT, IMAGE_WIDTH],
             true,
@@ -155,7 +149,7 @@ where
         );
         let kernel = VarTensor::new_advice(
             cs,
-            K,
+            Self::K,
             max(
                 OUT_CHANNELS * IN_CHANNELS * KERNEL_HEIGHT * KERNEL_WIDTH,
                 CLASSES * LEN,
@@ -167,7 +161,7 @@ where
 
         let bias = VarTensor::new_advice(
             cs,
-            K,
+            Self::K,
             max(OUT_CHANNELS, CLASSES),
             vec![OUT_CHANNELS],
             true,
@@ -175,7 +169,7 @@ where
         );
         let output = VarTensor::new_advice(
             cs,
-            K,
+            Self::K,
             max(OUT_CHANNELS * output_height * output_width, LEN),
             vec![OUT_CHANNELS, output_height, output_width],
             true,
@@ -205,8 +199,13 @@ where
         let input = input.reshape(&[LEN]);
         let output = output.reshape(&[LEN]);
 
-        let l1 =
-            LookupConfig::configure(cs, &input, &output, BITS, &[LookupOp::ReLU { scale: 32 }]);
+        let l1 = LookupConfig::configure(
+            cs,
+            &input,
+            &output,
+            BITS,
+            &[LookupOp::ReLU { scale: SCALE }],
+        );
 
         // tells the config layer to add an affine op to the circuit gate
         let affine_node = PolyNode {
@@ -250,7 +249,10 @@ where
                 ],
             )
             .unwrap();
-        let mut x = config.l1.layout(&mut layouter, &x).unwrap();
+        let mut x = config
+            .l1
+            .layout_with_scale(&mut layouter, &x, SCALE)
+            .unwrap();
         x.flatten();
         let l2out = config
             .l2
@@ -271,22 +273,149 @@ where
     }
 }
 
-pub fn runconv() {
-    const KERNEL_HEIGHT: usize = 5;
-    const KERNEL_WIDTH: usize = 5;
-    const OUT_CHANNELS: usize = 4;
-    const STRIDE: usize = 2;
-    const IMAGE_HEIGHT: usize = 28;
-    const IMAGE_WIDTH: usize = 28;
-    const IN_CHANNELS: usize = 1;
-    const PADDING: usize = 0;
-    const CLASSES: usize = 10;
-    const LEN: usize = {
-        OUT_CHANNELS
-            * ((IMAGE_HEIGHT + 2 * PADDING - KERNEL_HEIGHT) / STRIDE + 1)
-            * ((IMAGE_WIDTH + 2 * PADDING - KERNEL_WIDTH) / STRIDE + 1)
-    };
+impl<
+        F: FieldExt + TensorType,
+        const LEN: usize,
+        const CLASSES: usize,
+        const BITS: usize,
+        // Convolution
+        const KERNEL_HEIGHT: usize,
+        const KERNEL_WIDTH: usize,
+        const OUT_CHANNELS: usize,
+        const STRIDE: usize,
+        const IMAGE_HEIGHT: usize,
+        const IMAGE_WIDTH: usize,
+        const IN_CHANNELS: usize,
+        const PADDING: usize,
+    >
+    MyCircuit<
+        F,
+        LEN,
+        CLASSES,
+        BITS,
+        KERNEL_HEIGHT,
+        KERNEL_WIDTH,
+        OUT_CHANNELS,
+        STRIDE,
+        IMAGE_HEIGHT,
+        IMAGE_WIDTH,
+        IN_CHANNELS,
+        PADDING,
+    >
+where
+    Value<F>: TensorType,
+{
+    /// log2 of the number of rows of the circuit, such that the `BITS`-bit lookup table (plus blinding rows) fits.
+    pub const K: usize = BITS + 1;
+
+    /// Runs the witness forward pass (conv -> relu -> affine) and returns the values that the
+    /// `public_output` instance column must hold for a proof of this circuit to verify.
+    pub fn public_output(&self) -> Result<Vec<F>, TensorError> {
+        let x = convolution(
+            &vec![
+                to_i32(&self.input)?,
+                to_i32(&self.l0_params[0])?,
+                to_i32(&self.l0_params[1])?,
+            ],
+            (PADDING, PADDING),
+            (STRIDE, STRIDE),
+        )?;
+        let mut x = nonlinearities::relu(&x, SCALE);
+        x.flatten();
+        let out = affine(&vec![
+            x,
+            to_i32(&self.l2_params[0])?,
+            to_i32(&self.l2_params[1])?,
+        ])?;
+        Ok(out.iter().map(|e| i32_to_felt::<F>(*e)).collect())
+    }
+}
+
+/// Converts the known field elements of a [ValTensor::Value] to integers.
+fn to_i32<F: FieldExt + TensorType>(v: &ValTensor<F>) -> Result<Tensor<i32>, TensorError> {
+    match v {
+        ValTensor::Value { inner, dims } => {
+            let mut integers = vec![];
+            let _ = inner.map(|e| e.map(|f| integers.push(fieldutils::felt_to_i32(f))));
+            Tensor::new(Some(&integers), dims)
+        }
+        _ => Err(TensorError::WrongMethod),
+    }
+}
+
+const KERNEL_HEIGHT: usize = 5;
+const KERNEL_WIDTH: usize = 5;
+const OUT_CHANNELS: usize = 4;
+const STRIDE: usize = 2;
+const IMAGE_HEIGHT: usize = 28;
+const IMAGE_WIDTH: usize = 28;
+const IN_CHANNELS: usize = 1;
+const PADDING: usize = 0;
+const CLASSES: usize = 10;
+/// The log2 of the fixed point multiplier the parameters are quantized with.
+const SCALE_EXP: i32 = 5;
+/// The fixed point multiplier the parameters are quantized with, and so the scale the relu is declared with.
+const SCALE: usize = 1 << SCALE_EXP;
+const LEN: usize = {
+    OUT_CHANNELS
+        * ((IMAGE_HEIGHT + 2 * PADDING - KERNEL_HEIGHT) / STRIDE + 1)
+        * ((IMAGE_WIDTH + 2 * PADDING - KERNEL_WIDTH) / STRIDE + 1)
+};
+
+type MnistCircuit = MyCircuit<
+    F,
+    LEN,
+    CLASSES,
+    16,
+    KERNEL_HEIGHT,
+    KERNEL_WIDTH,
+    OUT_CHANNELS,
+    STRIDE,
+    IMAGE_HEIGHT,
+    IMAGE_WIDTH,
+    IN_CHANNELS,
+    PADDING,
+>;
+
+/// Quantizes pre-trained parameters at [SCALE], panicking on NaNs and infinities rather than casting them.
+fn quantize(values: &[f32], dims: &[usize]) -> ValTensor<F> {
+    let t = vector_to_quantized(values, dims, 0.0, SCALE_EXP).expect("parameters should be finite");
+    Tensor::known_from(&t.map(i32_to_felt::<F>)).map_known()
+}
+
+/// Builds the circuit for a single `[1, 28, 28]` image using the pre-trained parameters in `params.rs`.
+fn mnist_circuit(input: ValTensor<F>) -> MnistCircuit {
+    let myparams = params::Params::new();
+    let kernels: Vec<f32> = myparams
+        .kernels
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flatten()
+        .collect();
+    let l0_kernels = quantize(
+        &kernels,
+        &[OUT_CHANNELS, IN_CHANNELS, KERNEL_HEIGHT, KERNEL_WIDTH],
+    );
+
+    let l0_bias: ValTensor<F> = Tensor::<Value<F>>::from(
+        (0..OUT_CHANNELS).map(|_| Value::known(fieldutils::i32_to_felt(0))),
+    )
+    .into();
 
+    let l2_biases = quantize(&myparams.biases, &[CLASSES]);
+
+    let weights: Vec<f32> = myparams.weights.into_iter().flatten().collect();
+    let l2_weights = quantize(&weights, &[CLASSES, LEN]);
+
+    MnistCircuit {
+        input,
+        l0_params: [l0_kernels, l0_bias],
+        l2_params: [l2_weights, l2_biases],
+    }
+}
+
+pub fn runconv() {
     // Load the parameters and preimage from somewhere
 
     let Mnist {
@@ -310,68 +439,60 @@ pub fn runconv() {
 
     println!("The first digit is a {:?}", train_labels[0]);
 
-    let mut input: ValTensor<F> = train_data
-        .get_slice(&[0..1, 0..28, 0..28])
-        .unwrap()
-        .map(Value::known)
-        .into();
+    let mut input: ValTensor<F> =
+        Tensor::known_from(&train_data.get_slice(&[0..1, 0..28, 0..28]).unwrap()).map_known();
 
     input.reshape(&[1, 28, 28]).unwrap();
 
-    let myparams = params::Params::new();
-    let mut l0_kernels: ValTensor<F> = Tensor::<Value<F>>::from(
-        myparams
-            .kernels
-            .clone()
-            .into_iter()
-            .flatten()
-            .flatten()
-            .flatten()
-            .map(|fl| {
-                let dx = (fl as f32) * 32_f32;
-                let rounded = dx.round();
-                let integral: i32 = unsafe { rounded.to_int_unchecked() };
-                let felt = fieldutils::i32_to_felt(integral);
-                Value::known(felt)
-            }),
-    )
-    .into();
+    let circuit = mnist_circuit(input);
 
-    l0_kernels
-        .reshape(&[OUT_CHANNELS, IN_CHANNELS, KERNEL_HEIGHT, KERNEL_WIDTH])
-        .unwrap();
+    #[cfg(feature = "dev-graph")]
+    {
+        println!("Plotting");
+        use plotters::prelude::*;
 
-    let l0_bias: ValTensor<F> = Tensor::<Value<F>>::from(
-        (0..OUT_CHANNELS).map(|_| Value::known(fieldutils::i32_to_felt(0))),
-    )
-    .into();
+        let root = BitMapBackend::new("conv2dmnist-layout.png", (2048, 7680)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root
+            .titled("Conv -> ReLU -> Affine -> ReLU", ("sans-serif", 60))
+            .unwrap();
 
-    let l2_biases: ValTensor<F> = Tensor::<Value<F>>::from(myparams.biases.into_iter().map(|fl| {
-        let dx = fl * 32_f32;
-        let rounded = dx.round();
-        let integral: i32 = unsafe { rounded.to_int_unchecked() };
-        let felt = fieldutils::i32_to_felt(integral);
-        Value::known(felt)
-    }))
-    .into();
+        halo2_proofs::dev::CircuitLayout::default()
+            .render(13, &circuit, &root)
+            .unwrap();
+        return;
+    }
 
-    let mut l2_weights: ValTensor<F> =
-        Tensor::<Value<F>>::from(myparams.weights.into_iter().flatten().map(|fl| {
-            let dx = fl * 32_f32;
-            let rounded = dx.round();
-            let integral: i32 = unsafe { rounded.to_int_unchecked() };
-            let felt = fieldutils::i32_to_felt(integral);
-            Value::known(felt)
-        }))
-        .into();
+    // the instance is computed from the witness so that it matches the witnessed output
+    let pi_inner: Vec<F> = circuit
+        .public_output()
+        .expect("forward pass should not fail");
 
-    l2_weights.reshape(&[CLASSES, LEN]).unwrap();
+    //	Real proof
+    circuit
+        .prove_and_verify(pi_inner)
+        .expect("proof should verify");
+}
 
-    let circuit = MyCircuit::<
+impl<
+        const LEN: usize,
+        const CLASSES: usize,
+        const BITS: usize,
+        // Convolution
+        const KERNEL_HEIGHT: usize,
+        const KERNEL_WIDTH: usize,
+        const OUT_CHANNELS: usize,
+        const STRIDE: usize,
+        const IMAGE_HEIGHT: usize,
+        const IMAGE_WIDTH: usize,
+        const IN_CHANNELS: usize,
+        const PADDING: usize,
+    >
+    MyCircuit<
         F,
         LEN,
-        10,
-        16,
+        CLASSES,
+        BITS,
         KERNEL_HEIGHT,
         KERNEL_WIDTH,
         OUT_CHANNELS,
@@ -380,77 +501,132 @@ pub fn runconv() {
         IMAGE_WIDTH,
         IN_CHANNELS,
         PADDING,
-    > {
-        input,
-        l0_params: [l0_kernels, l0_bias],
-        l2_params: [l2_weights, l2_biases],
-    };
+    >
+{
+    /// Runs keygen, proves the circuit against the `public_output` instance and verifies the proof, over IPA params
+    /// of `2^K` rows.
+    pub fn prove_and_verify(self, public_output: Vec<F>) -> Result<(), Box<dyn StdError>> {
+        let params: ParamsIPA<vesta::Affine> = ParamsIPA::new(Self::K as u32);
+        let empty_circuit = self.without_witnesses();
+        // Initialize the proving key
+        let now = Instant::now();
+        let vk = keygen_vk(&params, &empty_circuit)?;
+        println!("VK took {}", now.elapsed().as_secs());
+        let now = Instant::now();
+        let pk = keygen_pk(&params, vk, &empty_circuit)?;
+        println!("PK took {}", now.elapsed().as_secs());
+        let now = Instant::now();
+        let proof = ipa::prove(&params, &pk, self, &[public_output.clone()])?;
+        println!("Proof took {}", now.elapsed().as_secs());
+        let now = Instant::now();
+        Self::verify(&params, pk.get_vk(), &public_output, &proof)?;
+        println!("Verify took {}", now.elapsed().as_secs());
+        Ok(())
+    }
 
-    #[cfg(feature = "dev-graph")]
-    {
-        println!("Plotting");
-        use plotters::prelude::*;
+    /// Verifies `proof` against the `public_output` instance, which [ipa::verify] first checks holds one value per
+    /// class, as the `public_output` column is constrained to.
+    pub fn verify(
+        params: &ParamsIPA<vesta::Affine>,
+        vk: &VerifyingKey<vesta::Affine>,
+        public_output: &[F],
+        proof: &[u8],
+    ) -> Result<(), Box<dyn StdError>> {
+        ipa::verify(params, vk, &[public_output.to_vec()], &[CLASSES], proof)
+    }
+}
 
-        let root = BitMapBackend::new("conv2dmnist-layout.png", (2048, 7680)).into_drawing_area();
-        root.fill(&WHITE).unwrap();
-        let root = root
-            .titled("Conv -> ReLU -> Affine -> ReLU", ("sans-serif", 60))
-            .unwrap();
+fn main() {
+    runconv()
+}
 
-        halo2_proofs::dev::CircuitLayout::default()
-            .render(13, &circuit, &root)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ezkl::circuit::CircuitError;
+    use halo2_proofs::dev::MockProver;
+
+    #[test]
+    #[ignore = "mock proves the full mnist circuit at K = 17, run with --ignored"]
+    fn public_output_is_a_valid_instance() {
+        // a synthetic image so the test doesn't depend on the MNIST download
+        let mut input: ValTensor<F> = Tensor::from(
+            (0..IMAGE_HEIGHT * IMAGE_WIDTH)
+                .map(|i| Value::known(i32_to_felt::<F>((i % 16) as i32))),
+        )
+        .into();
+        input
+            .reshape(&[IN_CHANNELS, IMAGE_HEIGHT, IMAGE_WIDTH])
             .unwrap();
-        return;
+
+        let circuit = mnist_circuit(input);
+        let public_output = circuit.public_output().unwrap();
+        assert_eq!(public_output.len(), CLASSES);
+
+        let prover = MockProver::run(
+            MnistCircuit::K as u32,
+            &circuit,
+            vec![public_output.clone()],
+        )
+        .unwrap();
+        prover.assert_satisfied();
+
+        // a perturbed instance should be rejected
+        let mut wrong_output = public_output;
+        wrong_output[0] += F::from(1_u64);
+        let prover = MockProver::run(MnistCircuit::K as u32, &circuit, vec![wrong_output]).unwrap();
+        assert!(prover.verify().is_err());
     }
 
-    let public_input: Tensor<i32> = vec![
-        -25124i32, -19304, -16668, -4399, -6209, -4548, -2317, -8349, -6117, -23461,
-    ]
-    .into_iter()
-    .into();
+    // a conv -> relu -> affine circuit over a 4x4 image, small enough to run keygen and prove in seconds
+    type TinyCircuit = MyCircuit<F, 9, 2, 8, 2, 2, 1, 1, 4, 4, 1, 0>;
 
-    let pi_inner: Tensor<F> = public_input.map(i32_to_felt::<F>);
-    let pi_for_real_prover: &[&[&[F]]] = &[&[&pi_inner]];
+    fn tiny_circuit() -> TinyCircuit {
+        let known = |values: &[i32], dims: &[usize]| -> ValTensor<F> {
+            let t = Tensor::new(Some(values), dims).unwrap();
+            Tensor::known_from(&t.map(i32_to_felt::<F>)).map_known()
+        };
+        let image = (0..16).map(|i| i % 4).collect::<Vec<_>>();
+        let weights = (0..18).map(|i| i % 3 - 1).collect::<Vec<_>>();
+        TinyCircuit {
+            input: known(&image, &[1, 4, 4]),
+            l0_params: [known(&[1, 2, -1, 1], &[1, 1, 2, 2]), known(&[3], &[1])],
+            l2_params: [known(&weights, &[2, 9]), known(&[1, -2], &[2])],
+        }
+    }
 
-    //	Real proof
-    let params: ParamsIPA<vesta::Affine> = ParamsIPA::new(K as u32);
-    let empty_circuit = circuit.without_witnesses();
-    // Initialize the proving key
-    let now = Instant::now();
-    let vk = keygen_vk(&params, &empty_circuit).expect("keygen_vk should not fail");
-    println!("VK took {}", now.elapsed().as_secs());
-    let now = Instant::now();
-    let pk = keygen_pk(&params, vk, &empty_circuit).expect("keygen_pk should not fail");
-    println!("PK took {}", now.elapsed().as_secs());
-    let now = Instant::now();
-    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
-    let mut rng = OsRng;
-    create_proof::<IPACommitmentScheme<_>, ProverIPA<_>, _, _, _, _>(
-        &params,
-        &pk,
-        &[circuit],
-        pi_for_real_prover,
-        &mut rng,
-        &mut transcript,
-    )
-    .expect("proof generation should not fail");
-    let proof = transcript.finalize();
-    //println!("{:?}", proof);
-    println!("Proof took {}", now.elapsed().as_secs());
-    let now = Instant::now();
-    let strategy = SingleStrategy::new(&params);
-    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
-    assert!(verify_proof(
-        &params,
-        pk.get_vk(),
-        strategy,
-        pi_for_real_prover,
-        &mut transcript
-    )
-    .is_ok());
-    println!("Verify took {}", now.elapsed().as_secs());
-}
+    #[test]
+    fn tiny_circuit_proves_and_verifies() {
+        let circuit = tiny_circuit();
+        let public_output = circuit.public_output().unwrap();
+        assert_eq!(public_output.len(), 2);
 
-fn main() {
-    runconv()
+        assert!(circuit
+            .clone()
+            .prove_and_verify(public_output.clone())
+            .is_ok());
+
+        // a proof against a perturbed instance should fail to verify
+        let mut wrong_output = public_output;
+        wrong_output[0] += F::from(1_u64);
+        assert!(circuit.prove_and_verify(wrong_output).is_err());
+    }
+
+    #[test]
+    fn short_instance_is_rejected_before_verifying() {
+        let circuit = tiny_circuit();
+        let params: ParamsIPA<vesta::Affine> = ParamsIPA::new(TinyCircuit::K as u32);
+        let vk = keygen_vk(&params, &circuit.without_witnesses()).unwrap();
+
+        let mut public_output = circuit.public_output().unwrap();
+        public_output.pop();
+        let err = TinyCircuit::verify(&params, &vk, &public_output, &[]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CircuitError>(),
+            Some(CircuitError::InstanceLenMismatch {
+                expected: 2,
+                got: 1
+            })
+        ));
+    }
 }
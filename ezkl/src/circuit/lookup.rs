@@ -22,9 +22,16 @@ pub enum Op {
     ReLU {
         scale: usize,
     },
+    ThresholdedReLU {
+        scale: usize,
+        theta: i32,
+    },
     Sqrt {
         scales: (usize, usize),
     },
+    Square {
+        scale: usize,
+    },
     LeakyReLU {
         scale: usize,
         slope: eq_float::F32,
@@ -36,6 +43,9 @@ pub enum Op {
     Sigmoid {
         scales: (usize, usize),
     },
+    Exp {
+        scales: (usize, usize),
+    },
 }
 
 impl fmt::Display for Op {
@@ -43,6 +53,9 @@ impl fmt::Display for Op {
         match self {
             Op::Div { scale } => write!(f, "div  w/ scale: {}", scale),
             Op::ReLU { scale } => write!(f, "relu w/ scale: {}", scale),
+            Op::ThresholdedReLU { scale, theta } => {
+                write!(f, "thresholded-relu w/ scale: {}, theta: {}", scale, theta)
+            }
             Op::LeakyReLU { scale, slope } => {
                 write!(f, "leaky-relu w/ scale: {}, slope: {}", scale, slope)
             }
@@ -50,7 +63,9 @@ impl fmt::Display for Op {
                 write!(f, "leaky-relu w/ scale: {}, slopes: {:#?}", scale, slopes)
             }
             Op::Sigmoid { scales } => write!(f, "sigmoid  w/ scale: {}", scales.0),
+            Op::Exp { scales } => write!(f, "exp  w/ scale: {}", scales.0),
             Op::Sqrt { scales } => write!(f, "sqrt  w/ scale: {}", scales.0),
+            Op::Square { scale } => write!(f, "square  w/ scale: {}", scale),
         }
     }
 }
@@ -60,11 +75,39 @@ impl Op {
     pub fn f(&self, x: Tensor<i32>) -> Tensor<i32> {
         match &self {
             Op::Div { scale } => const_div(&x, *scale as i32),
-            Op::ReLU { scale } => leakyrelu(&x, *scale, 0_f32),
+            Op::ReLU { scale } => relu(&x, *scale),
+            Op::ThresholdedReLU { scale, theta } => thresholded_relu(&x, *scale, *theta),
             Op::LeakyReLU { scale, slope } => leakyrelu(&x, *scale, slope.0),
             Op::PReLU { scale, slopes } => leakyrelu(&x, *scale, slopes[0].0),
             Op::Sigmoid { scales } => sigmoid(&x, scales.0, scales.1),
+            Op::Exp { scales } => exp(&x, scales.0, scales.1),
             Op::Sqrt { scales } => sqrt(&x, scales.0, scales.1),
+            Op::Square { scale } => square(&x, *scale),
+        }
+    }
+
+    /// The scale (fixed point multiplier) the operation expects its input to be quantized with. `None` for division,
+    /// whose `scale` is a divisor applied to the input rather than the input's own scale.
+    pub fn scale(&self) -> Option<usize> {
+        match &self {
+            Op::Div { .. } => None,
+            Op::ReLU { scale }
+            | Op::ThresholdedReLU { scale, .. }
+            | Op::Square { scale }
+            | Op::LeakyReLU { scale, .. }
+            | Op::PReLU { scale, .. } => Some(*scale),
+            Op::Sigmoid { scales } | Op::Sqrt { scales } | Op::Exp { scales } => Some(scales.0),
+        }
+    }
+
+    /// Errors if `scale`, the scale the witness was quantized with, doesn't match the input scale the operation was
+    /// declared with, as a mismatch produces wrong (but provable) outputs. Operations without an input scale always pass.
+    pub fn check_scale(&self, scale: usize) -> Result<(), CircuitError> {
+        match self.scale() {
+            Some(expected) if expected != scale => {
+                Err(CircuitError::ScaleMismatch(expected, scale))
+            }
+            _ => Ok(()),
         }
     }
 
@@ -75,6 +118,14 @@ impl Op {
     }
 }
 
+/// Returns the `(input, output)` pairs a lookup table for `op` contains for every input in `min..=max`, for instance
+/// to check a table against the corresponding witness function.
+pub fn populate_table(op: &Op, min: i32, max: i32) -> Vec<(i32, i32)> {
+    let inputs = Tensor::from(min..=max);
+    let outputs = op.f(inputs.clone());
+    inputs.into_iter().zip(outputs).collect()
+}
+
 /// Halo2 lookup table for element wise non-linearities.
 // Table that should be reused across all lookups (so no Clone)
 #[derive(Clone, Debug)]
@@ -105,6 +156,11 @@ impl<F: FieldExt> Table<F> {
         }
     }
     /// Assigns values to the constraints generated when calling `configure`.
+    ///
+    /// The table covers the signed domain `-2^(bits - 1)..2^(bits - 1)`. Negative inputs are stored as their field
+    /// representation `p - |x|` (see [i32_to_felt]), which is also how negative witness values are assigned, such that a
+    /// witnessed `-5` looks up the row holding `op(-5)`. The witness side recovers the signed integer with
+    /// [felt_to_i32] before applying the operation.
     pub fn layout(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), Box<dyn Error>> {
         if self.is_assigned {
             return Err(Box::new(CircuitError::TableAlreadyAssigned));
@@ -274,6 +330,20 @@ impl<F: FieldExt + TensorType> Config<F> {
         Self::configure_with_table(cs, input, output, table)
     }
 
+    /// Calls `layout` after checking that `scale`, the scale `values` were quantized with, matches the scale each of the
+    /// table's operations was declared with.
+    pub fn layout_with_scale(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        values: &ValTensor<F>,
+        scale: usize,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        for nl in self.table.borrow().nonlinearities.iter() {
+            nl.check_scale(scale)?;
+        }
+        self.layout(layouter, values)
+    }
+
     /// Assigns values to the variables created when calling `configure`.
     /// Values are supplied as a 1-element array of `[input]` VarTensors.
     pub fn layout(
@@ -387,4 +457,261 @@ mod tests {
         let prover = MockProver::run(4_u32, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
     }
+
+    #[derive(Clone)]
+    struct ScaledReLUCircuit<F: FieldExt + TensorType> {
+        pub input: ValTensor<F>,
+        pub input_scale: usize,
+    }
+
+    impl<F: FieldExt + TensorType> Circuit<F> for ScaledReLUCircuit<F> {
+        type Config = Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..2)
+                .map(|_| VarTensor::new_advice(cs, 4, 1, vec![1], true, 512))
+                .collect::<Vec<_>>();
+
+            let nl = Op::ReLU { scale: 2 };
+
+            Self::Config::configure(cs, &advices[0], &advices[1], 2, &[nl])
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config
+                .layout_with_scale(&mut layouter, &self.input, self.input_scale)
+                .map_err(|_| Error::Synthesis)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn relu_scale_mismatch_is_caught() {
+        let op = Op::ReLU { scale: 32 };
+        assert!(op.check_scale(32).is_ok());
+        assert!(matches!(
+            op.check_scale(16),
+            Err(CircuitError::ScaleMismatch(32, 16))
+        ));
+        // a division's scale is a divisor, not the scale of its input
+        assert!(Op::Div { scale: 128 }.check_scale(32).is_ok());
+
+        let input: Tensor<Value<F>> =
+            Tensor::new(Some(&[Value::<F>::known(F::from(1_u64))]), &[1]).unwrap();
+        let circuit = ScaledReLUCircuit::<F> {
+            input: ValTensor::from(input),
+            input_scale: 2,
+        };
+        let prover = MockProver::run(4_u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = ScaledReLUCircuit::<F> {
+            input_scale: 4,
+            ..circuit
+        };
+        assert!(MockProver::run(4_u32, &circuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn relu_table_matches_witness() {
+        let table = populate_table(&Op::ReLU { scale: 2 }, -8, 7);
+        assert_eq!(table.len(), 16);
+        assert_eq!(table[0], (-8, 0));
+        assert_eq!(table[15], (7, 4));
+
+        let inputs = Tensor::<i32>::new(Some(&(-8..8).collect::<Vec<_>>()), &[16]).unwrap();
+        let expected: Vec<(i32, i32)> = inputs
+            .iter()
+            .cloned()
+            .zip(relu(&inputs, 2).iter().cloned())
+            .collect();
+        assert_eq!(table, expected);
+    }
+
+    /// An [Op] laid out over `values` from its table by [TableDomainCircuit].
+    trait TableOp: Clone {
+        fn op() -> Op;
+
+        /// Defaults to every input of a 4 bit table.
+        fn values() -> Vec<i32> {
+            (-8..8).collect()
+        }
+    }
+
+    #[derive(Clone)]
+    struct ReLUOp;
+
+    impl TableOp for ReLUOp {
+        fn op() -> Op {
+            Op::ReLU { scale: 1 }
+        }
+    }
+
+    #[derive(Clone)]
+    struct ExpOp;
+
+    impl TableOp for ExpOp {
+        fn op() -> Op {
+            Op::Exp { scales: (4, 2) }
+        }
+    }
+
+    #[derive(Clone)]
+    struct SqrtOp;
+
+    impl TableOp for SqrtOp {
+        fn op() -> Op {
+            Op::Sqrt { scales: (2, 4) }
+        }
+    }
+
+    #[derive(Clone)]
+    struct SquareOp;
+
+    impl TableOp for SquareOp {
+        fn op() -> Op {
+            Op::Square { scale: 2 }
+        }
+    }
+
+    #[derive(Clone)]
+    struct DivOp;
+
+    impl TableOp for DivOp {
+        fn op() -> Op {
+            Op::Div { scale: 2 }
+        }
+
+        fn values() -> Vec<i32> {
+            vec![-7, -3, 0, 3, 5]
+        }
+    }
+
+    #[derive(Clone)]
+    struct ThresholdedReLUOp;
+
+    impl TableOp for ThresholdedReLUOp {
+        fn op() -> Op {
+            Op::ThresholdedReLU { scale: 1, theta: 3 }
+        }
+
+        fn values() -> Vec<i32> {
+            vec![-4, 2, 3, 4, 5]
+        }
+    }
+
+    #[derive(Clone)]
+    struct TableDomainCircuit<F: FieldExt + TensorType, O: TableOp> {
+        pub input: ValTensor<F>,
+        pub expected: Vec<i32>,
+        _marker: PhantomData<O>,
+    }
+
+    impl<F: FieldExt + TensorType, O: TableOp> Circuit<F> for TableDomainCircuit<F, O> {
+        type Config = Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let len = O::values().len();
+            let advices = (0..2)
+                .map(|_| VarTensor::new_advice(cs, 5, len, vec![len], true, 512))
+                .collect::<Vec<_>>();
+
+            Self::Config::configure(cs, &advices[0], &advices[1], 4, &[O::op()])
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let output = config
+                .layout(&mut layouter, &self.input)
+                .map_err(|_| Error::Synthesis)?;
+
+            if let ValTensor::PrevAssigned { inner, .. } = output {
+                let mut evals = vec![];
+                for cell in inner.iter() {
+                    let _ = cell.value().map(|v| evals.push(felt_to_i32(*v)));
+                }
+                if !evals.is_empty() {
+                    assert_eq!(evals, self.expected);
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Proves `O` over its `values`, checking the outputs against `witness`.
+    fn prove_over_table_domain<O: TableOp>(witness: impl Fn(&Tensor<i32>) -> Tensor<i32>) {
+        let values = O::values();
+        let values = Tensor::<i32>::new(Some(&values), &[values.len()]).unwrap();
+        let expected = witness(&values);
+        let input: Tensor<Value<F>> = values.map(|x| Value::known(i32_to_felt(x)));
+
+        let circuit = TableDomainCircuit::<F, O> {
+            input: ValTensor::from(input),
+            expected: expected.to_vec(),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(5_u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn relucircuit_negative_inputs() {
+        // negative inputs wrap around the modulus but still index the table by their signed value
+        assert_eq!(felt_to_i32(i32_to_felt::<F>(-5)), -5);
+        assert_eq!(
+            Op::ReLU { scale: 1 }.f(Tensor::from([-5, -1, 3].into_iter()))[..],
+            [0, 0, 3]
+        );
+        prove_over_table_domain::<ReLUOp>(|x| relu(x, 1));
+    }
+
+    #[test]
+    fn divcircuit() {
+        prove_over_table_domain::<DivOp>(|x| const_div(x, 2));
+    }
+
+    #[test]
+    fn expcircuit() {
+        prove_over_table_domain::<ExpOp>(|x| exp(x, 4, 2));
+    }
+
+    #[test]
+    fn sqrtcircuit() {
+        prove_over_table_domain::<SqrtOp>(|x| sqrt(x, 2, 4));
+    }
+
+    #[test]
+    fn squarecircuit() {
+        prove_over_table_domain::<SquareOp>(|x| square(x, 2));
+    }
+
+    #[test]
+    fn thresholdedrelucircuit() {
+        // values just below, at, and just above theta = 3
+        let values = Tensor::<i32>::new(Some(&ThresholdedReLUOp::values()), &[5]).unwrap();
+        assert_eq!(
+            thresholded_relu(&values, 1, 3).to_vec(),
+            vec![0, 0, 0, 4, 5]
+        );
+        prove_over_table_domain::<ThresholdedReLUOp>(|x| thresholded_relu(x, 1, 3));
+    }
 }
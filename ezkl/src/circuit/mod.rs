@@ -8,6 +8,10 @@ pub mod range;
 /// Utility functions for building gates.
 pub mod utils;
 
+use ethers::utils::keccak256;
+use halo2_proofs::{arithmetic::FieldExt, plonk::ConstraintSystem};
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use thiserror::Error;
 
 /// circuit related errors.
@@ -22,4 +26,179 @@ pub enum CircuitError {
     /// A lookup table was was already assigned
     #[error("attempting to initialize an already instantiated lookup table")]
     TableAlreadyAssigned,
+    /// A tensor is too large for the columns it is being assigned to
+    #[error("cannot assign {0} elements to columns with space for {1}")]
+    CapacityExceeded(usize, usize),
+    /// The scale a lookup table was configured with differs from the scale of its witness
+    #[error("lookup table was configured with scale {0} but its witness is at scale {1}")]
+    ScaleMismatch(usize, usize),
+    /// An instance holds a different number of values than the column it is checked against
+    #[error("instance has {got} values but the circuit expects {expected}")]
+    InstanceLenMismatch {
+        /// the number of values the circuit constrains the instance column to
+        expected: usize,
+        /// the number of values supplied
+        got: usize,
+    },
+}
+
+/// A description of a circuit's structure: the fused operations in each polynomial gate, the operations in each lookup
+/// table, the dimensions of its tensors, and the number of rows `2^k`. Used to fingerprint a circuit, for instance
+/// to key cached proving / verifying keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CircuitDescription {
+    /// log2 of the number of rows in the circuit.
+    pub k: usize,
+    /// The nodes fused into each polynomial gate.
+    pub poly_nodes: Vec<Vec<polynomial::Node>>,
+    /// The operations in each lookup table.
+    pub lookup_ops: Vec<Vec<lookup::Op>>,
+    /// The dimensions of the circuit's tensors.
+    pub dims: Vec<Vec<usize>>,
+}
+
+impl CircuitDescription {
+    /// Returns a hex encoded keccak digest of the description, which is stable across runs and changes whenever an
+    /// op, a shape, or `k` does.
+    pub fn circuit_fingerprint(&self) -> String {
+        let mut hasher = ByteHasher::default();
+        self.hash(&mut hasher);
+        hex::encode(keccak256(&hasher.0))
+    }
+}
+
+/// The columns, selectors and lookups allocated when configuring (part of) a circuit, and the number of rows its
+/// tensors span. Useful for right-sizing `k` and the capacity of [VarTensor]s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ColumnUsage {
+    /// Number of advice columns.
+    pub advice: usize,
+    /// Number of fixed columns.
+    pub fixed: usize,
+    /// Number of instance columns.
+    pub instance: usize,
+    /// Number of selectors.
+    pub selectors: usize,
+    /// Number of lookup arguments.
+    pub lookups: usize,
+    /// Number of rows spanned by the tallest tensor, see [ColumnUsage::with_rows].
+    pub rows: usize,
+}
+
+impl ColumnUsage {
+    /// Everything allocated in `cs` so far.
+    pub fn of<F: FieldExt>(cs: &ConstraintSystem<F>) -> Self {
+        ColumnUsage {
+            advice: cs.num_advice_columns(),
+            fixed: cs.num_fixed_columns(),
+            instance: cs.num_instance_columns(),
+            selectors: cs.num_selectors(),
+            lookups: cs.lookups().len(),
+            rows: 0,
+        }
+    }
+
+    /// Runs `configure` (for instance a layer's `configure` method) and reports what it allocated in `cs`.
+    pub fn measure<F: FieldExt, R>(
+        cs: &mut ConstraintSystem<F>,
+        configure: impl FnOnce(&mut ConstraintSystem<F>) -> R,
+    ) -> (R, Self) {
+        let before = Self::of(cs);
+        let res = configure(cs);
+        let after = Self::of(cs);
+        let usage = ColumnUsage {
+            advice: after.advice - before.advice,
+            fixed: after.fixed - before.fixed,
+            instance: after.instance - before.instance,
+            selectors: after.selectors - before.selectors,
+            lookups: after.lookups - before.lookups,
+            rows: 0,
+        };
+        (res, usage)
+    }
+
+    /// Sets `rows` to the number of rows spanned by the tallest of `vars`.
+    pub fn with_rows(self, vars: &[&VarTensor]) -> Self {
+        ColumnUsage {
+            rows: vars.iter().map(|v| v.num_rows()).max().unwrap_or(0),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ColumnUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "advice: {}, fixed: {}, instance: {}, selectors: {}, lookups: {}, rows: {}",
+            self.advice, self.fixed, self.instance, self.selectors, self.lookups, self.rows
+        )
+    }
+}
+
+/// A [Hasher] which records the bytes it is fed, such that they can be digested with a cryptographic hash function.
+#[derive(Default)]
+struct ByteHasher(Vec<u8>);
+
+impl Hasher for ByteHasher {
+    fn finish(&self) -> u64 {
+        let digest = keccak256(&self.0);
+        u64::from_le_bytes(digest[..8].try_into().unwrap())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::polynomial::{InputType, Node, Op};
+    use super::*;
+
+    fn conv_description(stride: usize) -> CircuitDescription {
+        CircuitDescription {
+            k: 17,
+            poly_nodes: vec![vec![Node {
+                op: Op::Conv {
+                    padding: (0, 0),
+                    stride: (stride, stride),
+                },
+                input_order: vec![
+                    InputType::Input(0),
+                    InputType::Input(1),
+                    InputType::Input(2),
+                ],
+            }]],
+            lookup_ops: vec![vec![lookup::Op::ReLU { scale: 32 }]],
+            dims: vec![vec![1, 28, 28], vec![4, 1, 5, 5], vec![4]],
+        }
+    }
+
+    #[test]
+    fn identical_circuits_share_a_fingerprint() {
+        let a = conv_description(2);
+        let b = conv_description(2);
+        assert_eq!(a.circuit_fingerprint(), b.circuit_fingerprint());
+        // a keccak digest
+        assert_eq!(a.circuit_fingerprint().len(), 64);
+    }
+
+    #[test]
+    fn structural_changes_change_the_fingerprint() {
+        let base = conv_description(2).circuit_fingerprint();
+        assert_ne!(base, conv_description(1).circuit_fingerprint());
+
+        let mut other_k = conv_description(2);
+        other_k.k = 16;
+        assert_ne!(base, other_k.circuit_fingerprint());
+
+        let mut other_dims = conv_description(2);
+        other_dims.dims[0] = vec![1, 32, 32];
+        assert_ne!(base, other_dims.circuit_fingerprint());
+
+        let mut other_lookup = conv_description(2);
+        other_lookup.lookup_ops[0] = vec![lookup::Op::ReLU { scale: 16 }];
+        assert_ne!(base, other_lookup.circuit_fingerprint());
+    }
 }
@@ -26,6 +26,7 @@ pub enum Op {
     Matmul,
     Dot,
     Affine,
+    BiasAdd,
     BatchNorm,
     ScaleAndShift,
     Conv {
@@ -59,6 +60,7 @@ impl fmt::Display for Op {
             Op::Matmul => write!(f, "matmul"),
             Op::Dot => write!(f, "dot"),
             Op::Affine => write!(f, "affine"),
+            Op::BiasAdd => write!(f, "bias add"),
             Op::BatchNorm => write!(f, "batchnorm"),
             Op::ScaleAndShift => write!(f, "scale & shift"),
             Op::Conv { padding, stride } => {
@@ -117,6 +119,12 @@ impl Op {
             Op::Sub => sub(&inputs),
             Op::Mult => mult(&inputs),
             Op::Affine => affine(&inputs),
+            Op::BiasAdd => {
+                if 2 != inputs.len() {
+                    return Err(TensorError::DimMismatch("bias add inputs".to_string()));
+                }
+                add_channel_bias(&inputs[0], &inputs[1])
+            }
             Op::BatchNorm => scale_and_shift(&inputs),
             Op::ScaleAndShift => scale_and_shift(&inputs),
             Op::Matmul => matmul(&inputs),
@@ -158,7 +166,7 @@ impl Op {
 }
 
 /// Representation of a the inputs a [Node] can ingest. The inner type indexes over each of the types.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum InputType {
     /// an explicit input to the operations
     Input(usize),
@@ -167,7 +175,7 @@ pub enum InputType {
 }
 
 /// Representation of a single fuseable operation.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Node {
     /// the type of operation
     pub op: Op,
@@ -201,6 +209,25 @@ impl<F: FieldExt + TensorType> Config<F> {
         output: &VarTensor,
         nodes: &[Node],
     ) -> Self {
+        Self::try_configure(meta, inputs, output, nodes).expect("poly: invalid configuration")
+    }
+
+    /// As `configure`, but validates the shapes of the explicit inputs against the [Node]s first, such that for instance
+    /// a convolution bias which doesn't match the kernel's output channels errors before any gate is created.
+    /// # Arguments
+    /// * `inputs` - The explicit inputs to the operations. [Node]s index over these inputs using their `input_order` attribute. They can also index over the intermediate outputs of other [Node]s.
+    /// * `output` - The variable representing the (currently singular) output of the fused operations.
+    /// * `nodes` - The sequence of operations (in order of execution) that constitute the fused operation.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        inputs: &[VarTensor],
+        output: &VarTensor,
+        nodes: &[Node],
+    ) -> Result<Self, CircuitError> {
+        for node in nodes {
+            Self::validate_node(node, inputs)?;
+        }
+
         let mut config = Self {
             selector: meta.selector(),
             nodes: nodes.to_vec(),
@@ -236,7 +263,42 @@ impl<F: FieldExt + TensorType> Config<F> {
             Constraints::with_selector(selector, constraints)
         });
 
-        config
+        Ok(config)
+    }
+
+    /// Checks the dimensions of the explicit inputs a [Node] indexes over, where they can be known at configure time.
+    fn validate_node(node: &Node, inputs: &[VarTensor]) -> Result<(), CircuitError> {
+        let mut op = &node.op;
+        while let Op::Rescaled { inner, .. } = op {
+            op = &**inner;
+        }
+        let dims = |i: usize| match node.input_order.get(i) {
+            Some(InputType::Input(u)) => inputs.get(*u).map(|v| v.dims()),
+            _ => None,
+        };
+
+        if let Op::Conv { .. } = op {
+            if let (Some(kernel), Some(bias)) = (dims(1), dims(2)) {
+                if (kernel.len() != 4) || (bias.len() != 1) || (bias[0] != kernel[0]) {
+                    return Err(CircuitError::DimMismatch(format!(
+                        "conv bias of shape {:?} for a kernel with {} output channels",
+                        bias,
+                        kernel.first().unwrap_or(&0)
+                    )));
+                }
+            }
+        }
+        if let Op::BiasAdd = op {
+            if let (Some(input), Some(bias)) = (dims(0), dims(1)) {
+                if input.is_empty() || (bias.len() != 1) || (bias[0] != input[0]) {
+                    return Err(CircuitError::DimMismatch(format!(
+                        "bias of shape {:?} for an input of shape {:?}",
+                        bias, input
+                    )));
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Assigns variables to the regions created when calling `configure`.
@@ -321,11 +383,12 @@ impl<F: FieldExt + TensorType> Config<F> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fieldutils::i32_to_felt;
     use halo2_proofs::{
         arithmetic::{Field, FieldExt},
         circuit::{Layouter, SimpleFloorPlanner, Value},
         dev::MockProver,
-        plonk::{Circuit, ConstraintSystem, Error},
+        plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
     };
     use halo2curves::pasta::pallas;
     use halo2curves::pasta::Fp as F;
@@ -384,6 +447,223 @@ mod tests {
         }
     }
 
+    /// A [Node] laid out by [NodeCircuit], alongside the shapes of its inputs and output.
+    trait NodeOp: Clone {
+        fn node() -> Node;
+        fn input_dims() -> Vec<Vec<usize>>;
+        fn output_dims() -> Vec<usize>;
+    }
+
+    /// Lays out `N::node()` over `inputs`, constraining its output to the instance column.
+    #[derive(Clone)]
+    struct NodeCircuit<F: FieldExt + TensorType, N: NodeOp> {
+        inputs: Vec<ValTensor<F>>,
+        _marker: PhantomData<N>,
+    }
+
+    impl<F: FieldExt + TensorType, N: NodeOp> Circuit<F> for NodeCircuit<F, N> {
+        type Config = (Config<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let inputs = N::input_dims()
+                .into_iter()
+                .map(|dims| VarTensor::new_advice(cs, K, dims.iter().product(), dims, true, 512))
+                .collect::<Vec<_>>();
+            let dims = N::output_dims();
+            let output = VarTensor::new_advice(cs, K, dims.iter().product(), dims, true, 512);
+            let instance = cs.instance_column();
+            cs.enable_equality(instance);
+
+            (
+                Config::configure(cs, &inputs, &output, &[N::node()]),
+                instance,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (mut config, instance): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let output = config
+                .layout(&mut layouter, &self.inputs)
+                .map_err(|_| Error::Synthesis)?;
+
+            match output {
+                ValTensor::PrevAssigned { inner, .. } => {
+                    inner.enum_map(|i, x| layouter.constrain_instance(x.cell(), instance, i))?;
+                    Ok(())
+                }
+                _ => Err(Error::Synthesis),
+            }
+        }
+    }
+
+    /// Runs the mock prover over `N` laid out on `inputs`, with `output` as the public output.
+    fn mock_prove<N: NodeOp>(inputs: &[Tensor<i32>], output: &Tensor<i32>) -> MockProver<F> {
+        let circuit = NodeCircuit::<F, N> {
+            inputs: inputs
+                .iter()
+                .map(|t| t.map(|e| Value::known(i32_to_felt(e))).into())
+                .collect(),
+            _marker: PhantomData,
+        };
+        let instance = output.map(i32_to_felt::<F>).to_vec();
+
+        MockProver::run(K as u32, &circuit, vec![instance]).unwrap()
+    }
+
+    #[derive(Clone)]
+    struct SumPoolOp;
+
+    impl NodeOp for SumPoolOp {
+        fn node() -> Node {
+            Node {
+                op: Op::SumPool {
+                    padding: (0, 0),
+                    stride: (1, 1),
+                    kernel_shape: (2, 2),
+                },
+                input_order: vec![InputType::Input(0)],
+            }
+        }
+
+        fn input_dims() -> Vec<Vec<usize>> {
+            vec![vec![1, 3, 3]]
+        }
+
+        fn output_dims() -> Vec<usize> {
+            vec![1, 2, 2]
+        }
+    }
+
+    #[test]
+    fn sumpoolcircuit() {
+        let values = Tensor::<i32>::new(Some(&[1, -2, 3, 4, 5, -6, 7, 8, 9]), &[1, 3, 3]).unwrap();
+        let expected = sumpool(&values, (0, 0), (1, 1), (2, 2)).unwrap();
+        assert_eq!(expected.to_vec(), vec![8, 0, 24, 16]);
+
+        mock_prove::<SumPoolOp>(&[values.clone()], &expected).assert_satisfied();
+
+        // a window sum which is off by one
+        let wrong = Tensor::<i32>::new(Some(&[8, 1, 24, 16]), &[1, 2, 2]).unwrap();
+        assert!(mock_prove::<SumPoolOp>(&[values], &wrong).verify().is_err());
+    }
+
+    #[derive(Clone)]
+    struct ScaleAndShiftOp;
+
+    impl NodeOp for ScaleAndShiftOp {
+        fn node() -> Node {
+            Node {
+                op: Op::ScaleAndShift,
+                input_order: vec![
+                    InputType::Input(0),
+                    InputType::Input(1),
+                    InputType::Input(2),
+                ],
+            }
+        }
+
+        fn input_dims() -> Vec<Vec<usize>> {
+            vec![vec![2, 2]; 3]
+        }
+
+        fn output_dims() -> Vec<usize> {
+            vec![2, 2]
+        }
+    }
+
+    #[test]
+    fn scaleandshiftcircuit() {
+        let x = Tensor::<i32>::new(Some(&[1, -2, 3, 4]), &[2, 2]).unwrap();
+        let k = Tensor::<i32>::new(Some(&[2, 3, -1, 0]), &[2, 2]).unwrap();
+        let b = Tensor::<i32>::new(Some(&[5, 0, 1, -7]), &[2, 2]).unwrap();
+        let expected = scale_and_shift(&vec![x.clone(), k.clone(), b.clone()]).unwrap();
+        assert_eq!(expected.to_vec(), vec![7, -6, -2, -7]);
+
+        mock_prove::<ScaleAndShiftOp>(&[x.clone(), k.clone(), b], &expected).assert_satisfied();
+
+        // a shift other than the one the output was computed with
+        let tampered = Tensor::<i32>::new(Some(&[5, 0, 2, -7]), &[2, 2]).unwrap();
+        assert!(mock_prove::<ScaleAndShiftOp>(&[x, k, tampered], &expected)
+            .verify()
+            .is_err());
+    }
+
+    #[derive(Clone)]
+    struct BiasAddOp;
+
+    impl NodeOp for BiasAddOp {
+        fn node() -> Node {
+            Node {
+                op: Op::BiasAdd,
+                input_order: vec![InputType::Input(0), InputType::Input(1)],
+            }
+        }
+
+        fn input_dims() -> Vec<Vec<usize>> {
+            vec![vec![2, 2, 2], vec![2]]
+        }
+
+        fn output_dims() -> Vec<usize> {
+            vec![2, 2, 2]
+        }
+    }
+
+    #[test]
+    fn biasaddcircuit() {
+        let x = Tensor::<i32>::new(Some(&[1, -2, 3, 4, 5, 6, -7, 8]), &[2, 2, 2]).unwrap();
+        let b = Tensor::<i32>::new(Some(&[10, -20]), &[2]).unwrap();
+        let expected = add_channel_bias(&x, &b).unwrap();
+        assert_eq!(expected.to_vec(), vec![11, 8, 13, 14, -15, -14, -27, -12]);
+
+        mock_prove::<BiasAddOp>(&[x.clone(), b], &expected).assert_satisfied();
+
+        // a bias other than the one the output was computed with
+        let tampered = Tensor::<i32>::new(Some(&[10, -19]), &[2]).unwrap();
+        assert!(mock_prove::<BiasAddOp>(&[x, tampered], &expected)
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn conv_bias_is_validated_at_configure() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let input = VarTensor::new_advice(&mut cs, K, 9, vec![1, 3, 3], true, 512);
+        let kernel = VarTensor::new_advice(&mut cs, K, 8, vec![2, 1, 2, 2], true, 512);
+        let short_bias = VarTensor::new_advice(&mut cs, K, 1, vec![1], true, 512);
+        let bias = VarTensor::new_advice(&mut cs, K, 2, vec![2], true, 512);
+        let output = VarTensor::new_advice(&mut cs, K, 8, vec![2, 2, 2], true, 512);
+        let conv_node = Node {
+            op: Op::Conv {
+                padding: (0, 0),
+                stride: (1, 1),
+            },
+            input_order: vec![
+                InputType::Input(0),
+                InputType::Input(1),
+                InputType::Input(2),
+            ],
+        };
+
+        let res = Config::try_configure(
+            &mut cs,
+            &[input.clone(), kernel.clone(), short_bias],
+            &output,
+            &[conv_node.clone()],
+        );
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+
+        let res = Config::try_configure(&mut cs, &[input, kernel, bias], &output, &[conv_node]);
+        assert!(res.is_ok());
+    }
+
     #[test]
     fn affinecircuit() {
         // parameters
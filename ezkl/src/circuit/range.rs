@@ -1,9 +1,9 @@
 use super::CircuitError;
 use crate::fieldutils::i32_to_felt;
-use crate::tensor::{TensorType, ValTensor, VarTensor};
+use crate::tensor::{Tensor, TensorType, ValTensor, VarTensor};
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::Layouter,
+    circuit::{Layouter, Value},
     plonk::{ConstraintSystem, Constraints, Expression, Selector},
 };
 use std::marker::PhantomData;
@@ -102,6 +102,123 @@ impl<F: FieldExt + TensorType> RangeCheckConfig<F> {
     }
 }
 
+/// Configuration for a range check on the difference between `input` and `expected` using a bit decomposition.
+/// The difference is constrained to lie in `[0, 2^n)`, where `n` is the number of bit tensors, by witnessing its bits,
+/// constraining each to be boolean, and constraining the difference to equal their recomposition. Unlike
+/// [RangeCheckConfig], whose gate degree grows linearly with the tolerance, this grows logarithmically with the range.
+#[derive(Debug, Clone)]
+pub struct BitRangeConfig<F: FieldExt + TensorType> {
+    input: VarTensor,
+    /// The value we are expecting the output of the circuit to match (within a range)
+    pub expected: VarTensor,
+    bits: Vec<VarTensor>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt + TensorType> BitRangeConfig<F> {
+    /// Configures a check that `input - expected` lies in `[0, 2^n)`.
+    /// # Arguments
+    /// * `input` - the input
+    /// * `expected` - the expected input we would have wanted to produce
+    /// * `bits` - `n` tensors of the same dimensions as `input`, the `j`-th of which holds the `j`-th bit of each difference.
+    pub fn configure(
+        cs: &mut ConstraintSystem<F>,
+        input: &VarTensor,
+        expected: &VarTensor,
+        bits: &[VarTensor],
+    ) -> Self {
+        assert!(
+            bits.len() < 128,
+            "bit range: at most 127 bits are supported"
+        );
+        let config = Self {
+            input: input.clone(),
+            expected: expected.clone(),
+            bits: bits.to_vec(),
+            selector: cs.selector(),
+            _marker: PhantomData,
+        };
+
+        cs.create_gate("bit range check", |cs| {
+            //   value  | expected |  b_0  | ... | b_(n-1) | q_bit_range_check
+            //  ---------------------------------------------------------------
+            //     v    |    e     |  b_0  | ... | b_(n-1) |         1
+
+            let q = cs.query_selector(config.selector);
+            let witnessed = input
+                .query(cs, 0)
+                .expect("bit range: failed to query input");
+            let expected = expected
+                .query(cs, 0)
+                .expect("bit range: failed to query expected value");
+            let bits: Vec<Tensor<Expression<F>>> = bits
+                .iter()
+                .map(|b| b.query(cs, 0).expect("bit range: failed to query bits"))
+                .collect();
+
+            let one = Expression::Constant(F::one());
+            let mut constraints = vec![];
+            for i in 0..witnessed.len() {
+                // v - e = b_0 + 2 * b_1 + ... + 2^(n-1) * b_(n-1)
+                let recomposed = bits
+                    .iter()
+                    .enumerate()
+                    .fold(Expression::Constant(F::zero()), |acc, (j, b)| {
+                        acc + b[i].clone() * Expression::Constant(F::from_u128(1 << j))
+                    });
+                constraints.push(witnessed[i].clone() - expected[i].clone() - recomposed);
+                // b_j * (1 - b_j) = 0
+                for b in bits.iter() {
+                    constraints.push(b[i].clone() * (one.clone() - b[i].clone()));
+                }
+            }
+            Constraints::with_selector(q, constraints)
+        });
+
+        config
+    }
+
+    /// Assigns variables to the regions created when calling `configure`, witnessing the bits of the difference.
+    /// # Arguments
+    /// * `layouter` - A Halo2 Layouter.
+    /// * `input` - The input values we want to express an error tolerance for
+    /// * `output` - The expected values
+    pub fn layout(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: ValTensor<F>,
+        output: ValTensor<F>,
+    ) -> Result<(), halo2_proofs::plonk::Error> {
+        layouter.assign_region(
+            || "bit range check layout",
+            |mut region| {
+                let offset = 0;
+
+                // Enable q_bit_range_check
+                self.selector.enable(&mut region, offset)?;
+
+                let witnessed = self.input.assign(&mut region, offset, &input)?;
+                let expected = self.expected.assign(&mut region, offset, &output)?;
+
+                for (j, bit) in self.bits.iter().enumerate() {
+                    let mut values: Tensor<Value<F>> =
+                        Tensor::from(witnessed.iter().zip(expected.iter()).map(|(w, e)| {
+                            w.value().zip(e.value()).map(|(w, e)| {
+                                let diff = (*w - *e).get_lower_128();
+                                F::from(((diff >> j) & 1) as u64)
+                            })
+                        }));
+                    values.reshape(witnessed.dims());
+                    bit.assign(&mut region, offset, &ValTensor::from(values))?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -159,6 +276,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_range_check_column_usage() {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let (config, usage) = crate::circuit::ColumnUsage::measure(&mut cs, |cs| {
+            <MyCircuit<Fp> as Circuit<Fp>>::configure(cs)
+        });
+        let usage = usage.with_rows(&[&config.input, &config.expected]);
+        assert_eq!(
+            usage,
+            crate::circuit::ColumnUsage {
+                advice: 2,
+                fixed: 0,
+                instance: 0,
+                selectors: 1,
+                lookups: 0,
+                rows: 1,
+            }
+        );
+        assert_eq!(
+            usage.to_string(),
+            "advice: 2, fixed: 0, instance: 0, selectors: 1, lookups: 0, rows: 1"
+        );
+    }
+
+    const BITS: usize = 4; // accepts differences in [0, 16) = [0, 2 x RANGE)
+
+    #[derive(Clone)]
+    struct BitCircuit<F: FieldExt + TensorType> {
+        input: ValTensor<F>,
+        output: ValTensor<F>,
+    }
+
+    impl<F: FieldExt + TensorType> Circuit<F> for BitCircuit<F> {
+        type Config = BitRangeConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..BITS + 2)
+                .map(|_| VarTensor::new_advice(cs, 4, 1, vec![1], true, 512))
+                .collect_vec();
+            BitRangeConfig::configure(cs, &advices[0], &advices[1], &advices[2..])
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout(
+                layouter.namespace(|| "assign value"),
+                self.input.clone(),
+                self.output.clone(),
+            )
+        }
+    }
+
+    fn felt_tensor(v: i32) -> ValTensor<Fp> {
+        let t = Tensor::new(Some(&[Value::known(i32_to_felt::<Fp>(v))]), &[1]).unwrap();
+        ValTensor::from(t)
+    }
+
+    #[test]
+    fn test_bit_range_check_matches_polynomial() {
+        let k = 4;
+        let expected = 20;
+        for diff in -4..(2 * RANGE as i32 + 4) {
+            let bit_circuit = BitCircuit::<Fp> {
+                input: felt_tensor(expected + diff),
+                output: felt_tensor(expected),
+            };
+            let bit_ok = MockProver::run(k, &bit_circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok();
+
+            // the polynomial check accepts [-RANGE, RANGE), so we shift the difference
+            let poly_circuit = MyCircuit::<Fp> {
+                input: felt_tensor(expected + diff - RANGE as i32),
+                output: felt_tensor(expected),
+            };
+            let poly_ok = MockProver::run(k, &poly_circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok();
+
+            assert_eq!(bit_ok, poly_ok, "difference {}", diff);
+            assert_eq!(
+                bit_ok,
+                (0..(1 << BITS)).contains(&diff),
+                "difference {}",
+                diff
+            );
+        }
+    }
+
     #[test]
     #[allow(clippy::assertions_on_constants)]
     fn test_range_check() {
@@ -57,7 +57,7 @@ fn verify_proof_circuit_kzg<
     vk: &VerifyingKey<G1Affine>,
     transcript: TranscriptType,
     strategy: Strategy,
-) -> Result<Strategy::Output, halo2_proofs::plonk::Error> {
+) -> Result<Strategy::Output, Box<dyn Error + Send + Sync>> {
     match transcript {
         TranscriptType::Blake => verify_proof_circuit::<
             Fr,
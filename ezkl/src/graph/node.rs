@@ -484,6 +484,12 @@ impl Node {
                             ..Default::default()
                         }
                     }
+                    // not (yet) produced when parsing onnx ops
+                    LookupOp::ThresholdedReLU { .. }
+                    | LookupOp::Square { .. }
+                    | LookupOp::Exp { .. } => {
+                        return Err(Box::new(GraphError::UnsupportedOp));
+                    }
                     LookupOp::Div { .. } => {
                         if inputs[1].out_dims.clone() != [1] {
                             return Err(Box::new(GraphError::NonConstantDiv));
@@ -1007,6 +1013,10 @@ impl Node {
                     PolyOp::Rescaled { .. } => {
                         return Err(Box::new(GraphError::RescalingError(opkind)));
                     }
+                    // not (yet) produced when parsing onnx ops
+                    PolyOp::BiasAdd => {
+                        return Err(Box::new(GraphError::UnsupportedOp));
+                    }
                     PolyOp::Identity => {
                         let input_node = &inputs[0];
                         Node {
@@ -1,8 +1,56 @@
+use crate::tensor::ops::assert_finite;
 use crate::tensor::{Tensor, TensorError};
 use anyhow::Result;
 use tract_onnx::prelude::{InferenceFact, Node};
 use tract_onnx::tract_hir::internal::InferenceOp;
 
+/// How quantization rounds scaled values which fall between two integers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Rounds to the nearest integer, and halfway cases away from zero (as [f32::round]).
+    #[default]
+    HalfUp,
+    /// Rounds to the nearest integer, and halfway cases to the nearest even integer (as commonly done in hardware).
+    HalfEven,
+    /// Rounds towards negative infinity.
+    Floor,
+    /// Rounds towards positive infinity.
+    Ceil,
+}
+
+impl RoundingMode {
+    /// Rounds `x` to an integer according to the mode.
+    /// ```
+    /// use ezkl::graph::utilities::RoundingMode;
+    /// assert_eq!(RoundingMode::HalfUp.round(0.5), 1.0);
+    /// assert_eq!(RoundingMode::HalfEven.round(0.5), 0.0);
+    /// assert_eq!(RoundingMode::Floor.round(0.5), 0.0);
+    /// assert_eq!(RoundingMode::Ceil.round(0.5), 1.0);
+    ///
+    /// assert_eq!(RoundingMode::HalfUp.round(-0.5), -1.0);
+    /// assert_eq!(RoundingMode::HalfEven.round(-0.5), 0.0);
+    /// assert_eq!(RoundingMode::HalfEven.round(1.5), 2.0);
+    /// assert_eq!(RoundingMode::HalfEven.round(2.5), 2.0);
+    /// assert_eq!(RoundingMode::HalfEven.round(2.4), 2.0);
+    /// assert_eq!(RoundingMode::Floor.round(-0.5), -1.0);
+    /// assert_eq!(RoundingMode::Ceil.round(-0.5), 0.0);
+    /// ```
+    pub fn round(&self, x: f64) -> f64 {
+        match self {
+            RoundingMode::HalfUp => x.round(),
+            RoundingMode::HalfEven => {
+                if (x - x.trunc()).abs() == 0.5 {
+                    2.0 * (x / 2.0).round()
+                } else {
+                    x.round()
+                }
+            }
+            RoundingMode::Floor => x.floor(),
+            RoundingMode::Ceil => x.ceil(),
+        }
+    }
+}
+
 // Warning: currently ignores stride information
 /// Quantizes an iterable of f32s to a [Tensor] of i32s using a fixed point representation.
 /// Arguments
@@ -16,13 +64,308 @@ pub fn vector_to_quantized(
     dims: &[usize],
     shift: f32,
     scale: i32,
+) -> Result<Tensor<i32>, TensorError> {
+    vector_to_quantized_with_rounding(vec, dims, shift, scale, RoundingMode::HalfUp)
+}
+
+/// As [vector_to_quantized], but rounds the scaled values according to `rounding` rather than to the nearest integer
+/// with halfway cases away from zero, e.g. to match the witnesses to a reference implementation.
+/// Arguments
+///
+/// * `vec` - the vector to quantize.
+/// * `dims` - the dimensionality of the resulting [Tensor].
+/// * `shift` - offset used in the fixed point representation.
+/// * `scale` - `2^scale` used in the fixed point representation.
+/// * `rounding` - how scaled values are rounded to integers.
+/// ```
+/// use ezkl::graph::utilities::{vector_to_quantized_with_rounding, RoundingMode};
+/// // 0.25 at scale 2^1 is 0.5
+/// let quantize = |mode| vector_to_quantized_with_rounding(&[0.25, -0.25, 0.75], &[3], 0.0, 1, mode).unwrap();
+/// assert_eq!(quantize(RoundingMode::HalfUp).to_vec(), vec![1, -1, 2]);
+/// assert_eq!(quantize(RoundingMode::HalfEven).to_vec(), vec![0, 0, 2]);
+/// assert_eq!(quantize(RoundingMode::Floor).to_vec(), vec![0, -1, 1]);
+/// assert_eq!(quantize(RoundingMode::Ceil).to_vec(), vec![1, 0, 2]);
+/// // non-finite values error rather than being quantized
+/// assert!(vector_to_quantized_with_rounding(&[0.25, f32::NAN], &[2], 0.0, 1, RoundingMode::HalfUp).is_err());
+/// ```
+pub fn vector_to_quantized_with_rounding(
+    vec: &[f32],
+    dims: &[usize],
+    shift: f32,
+    scale: i32,
+    rounding: RoundingMode,
 ) -> Result<Tensor<i32>, TensorError> {
     let mult = scale_to_multiplier(scale);
-    let scaled: Vec<i32> = vec
+    let scaled: Vec<f32> = vec.iter().map(|e| mult * e + shift).collect();
+    let scaled = Tensor::new(Some(&scaled), dims)?;
+    // NaNs and infinities don't have an integer representation
+    assert_finite(&scaled)?;
+    Ok(scaled.map(|e| rounding.round(e as f64) as i32))
+}
+
+/// Quantizes a [Tensor] of f64s to a [Tensor] of i32s using a fixed point representation. Prefer this to
+/// [vector_to_quantized] for large scales or values, where f32 doesn't have enough precision to round correctly.
+/// Errors with [TensorError::Overflow] if a quantized value doesn't fit in an i32, rather than saturating.
+/// Arguments
+///
+/// * `vec` - the tensor to quantize.
+/// * `shift` - offset used in the fixed point representation.
+/// * `scale` - `2^scale` used in the fixed point representation.
+/// ```
+/// use ezkl::graph::utilities::{vector_to_quantized, vector_to_quantized_f64};
+/// use ezkl::tensor::{Tensor, TensorError};
+/// // 2^20 + 0.03 at scale 2^5 is 33554432.96, but f32 can't represent the fractional part of 2^20 + 0.03
+/// let x = 1_048_576.03_f64;
+/// let single = vector_to_quantized(&[x as f32], &[1], 0.0, 5).unwrap();
+/// let double = vector_to_quantized_f64(&Tensor::new(Some(&[x]), &[1]).unwrap(), 0.0, 5).unwrap();
+/// assert_eq!(single[0], 33554432);
+/// assert_eq!(double[0], 33554433);
+/// // non-finite values error rather than being quantized
+/// assert!(vector_to_quantized_f64(&Tensor::new(Some(&[f64::INFINITY]), &[1]).unwrap(), 0.0, 5).is_err());
+/// // as do values past the range of an i32, here 2^26 * 2^5
+/// let big = Tensor::new(Some(&[67_108_864.0]), &[1]).unwrap();
+/// assert!(matches!(vector_to_quantized_f64(&big, 0.0, 5), Err(TensorError::Overflow(_))));
+/// ```
+pub fn vector_to_quantized_f64(
+    vec: &Tensor<f64>,
+    shift: f64,
+    scale: i32,
+) -> Result<Tensor<i32>, TensorError> {
+    let mult = scale_to_multiplier_f64(scale);
+    let scaled = vec.map(|e| (mult * e + shift).round());
+    // NaNs and infinities don't have an integer representation
+    assert_finite(&scaled)?;
+    let quantized: Vec<i32> = scaled
+        .iter()
+        .map(|e| {
+            if *e < i32::MIN as f64 || *e > i32::MAX as f64 {
+                return Err(TensorError::Overflow(format!(
+                    "vector_to_quantized_f64: {} doesn't fit in an i32",
+                    e
+                )));
+            }
+            Ok(*e as i32)
+        })
+        .collect::<Result<_, _>>()?;
+    Tensor::new(Some(&quantized), vec.dims())
+}
+
+/// Converts a [Tensor] of fixed point i32s back to f64s, the inverse of [vector_to_quantized_f64].
+/// Arguments
+///
+/// * `t` - the tensor to dequantize.
+/// * `shift` - offset used in the fixed point representation.
+/// * `scale` - `2^scale` used in the fixed point representation.
+pub fn dequantize_f64(t: &Tensor<i32>, shift: f64, scale: i32) -> Tensor<f64> {
+    let mult = scale_to_multiplier_f64(scale);
+    t.map(|e| (e as f64 - shift) / mult)
+}
+
+/// Quantizes a [Tensor] of f32s to a [Tensor] of i32s using a different fixed point multiplier for each slice along
+/// `axis`, as used for per-channel quantized convolution weights.
+/// Arguments
+///
+/// * `a` - the tensor to quantize.
+/// * `scales` - the multiplier for each slice, of length `a.dims()[axis]`.
+/// * `axis` - the axis the multipliers are applied along.
+/// ```
+/// use ezkl::graph::utilities::quantize_per_channel;
+/// use ezkl::tensor::Tensor;
+/// // two output channels of a 1x1 conv kernel
+/// let kernel = Tensor::<f32>::new(Some(&[0.5, -0.25, 0.5, -0.25]), &[2, 2, 1, 1]).unwrap();
+/// let quantized = quantize_per_channel(&kernel, &[4.0, 16.0], 0).unwrap();
+/// assert_eq!(quantized, Tensor::<i32>::new(Some(&[2, -1, 8, -4]), &[2, 2, 1, 1]).unwrap());
+/// ```
+pub fn quantize_per_channel(
+    a: &Tensor<f32>,
+    scales: &[f32],
+    axis: usize,
+) -> Result<Tensor<i32>, TensorError> {
+    if axis >= a.dims().len() || scales.len() != a.dims()[axis] {
+        return Err(TensorError::DimMismatch("quantize_per_channel".to_string()));
+    }
+    assert_finite(a)?;
+    // number of contiguous elements sharing a channel
+    let inner: usize = a.dims()[axis + 1..].iter().product();
+    let scaled: Vec<i32> = a
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (scales[(i / inner) % scales.len()] * e).round() as i32)
+        .collect();
+    Tensor::new(Some(&scaled), a.dims())
+}
+
+/// Divides each slice of a [Tensor] of i32s along `axis` by its own multiplier, rounding to the nearest integer. This is
+/// the counterpart to [quantize_per_channel], and brings the per-channel quantized output of a convolution back to a
+/// common scale (e.g. dividing by `scales[c] / common` for each channel `c`). Errors with
+/// [TensorError::NotFinite] if a divisor is NaN or infinite, and with [TensorError::ZeroDivisor] if one is zero
+/// (including `-0.0`), rather than casting the resulting infinity or NaN to an integer.
+/// Arguments
+///
+/// * `a` - the tensor to rescale.
+/// * `scale` - the divisor for each slice, of length `a.dims()[axis]`.
+/// * `axis` - the axis the divisors are applied along.
+/// ```
+/// use ezkl::graph::utilities::div_channel_scale;
+/// use ezkl::tensor::Tensor;
+/// // two channels, quantized with multipliers 4 and 16, brought back to a common multiplier of 4
+/// let x = Tensor::<i32>::new(Some(&[8, -4, 32, -18]), &[2, 2]).unwrap();
+/// let rescaled = div_channel_scale(&x, &[1.0, 4.0], 0).unwrap();
+/// assert_eq!(rescaled, Tensor::<i32>::new(Some(&[8, -4, 8, -5]), &[2, 2]).unwrap());
+/// // the scales must match the channels
+/// assert!(div_channel_scale(&x, &[1.0, 4.0, 2.0], 0).is_err());
+/// // and be finite and non-zero
+/// assert!(div_channel_scale(&x, &[1.0, 0.0], 0).is_err());
+/// assert!(div_channel_scale(&x, &[-0.0, 4.0], 0).is_err());
+/// assert!(div_channel_scale(&x, &[f32::NAN, 4.0], 0).is_err());
+/// ```
+pub fn div_channel_scale(
+    a: &Tensor<i32>,
+    scale: &[f32],
+    axis: usize,
+) -> Result<Tensor<i32>, TensorError> {
+    if axis >= a.dims().len() || scale.len() != a.dims()[axis] {
+        return Err(TensorError::DimMismatch("div_channel_scale".to_string()));
+    }
+    if let Some(i) = scale.iter().position(|s| !s.is_finite()) {
+        return Err(TensorError::NotFinite(i));
+    }
+    if let Some(i) = scale.iter().position(|s| *s == 0.0) {
+        return Err(TensorError::ZeroDivisor(i));
+    }
+    // number of contiguous elements sharing a channel
+    let inner: usize = a.dims()[axis + 1..].iter().product();
+    let scaled: Vec<i32> = a
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (*e as f32 / scale[(i / inner) % scale.len()]).round() as i32)
+        .collect();
+    Tensor::new(Some(&scaled), a.dims())
+}
+
+/// Folds a batch norm layer, which multiplies each output channel `c` by `bn_scale[c]` and then adds `bn_shift[c]`, into
+/// the preceding convolution's kernel (of shape `[output_channels, input_channels, kernel_height, kernel_width]`) and
+/// bias (of length `output_channels`). Returns the folded kernel and bias, such that the batch norm layer (and its
+/// constraints) can be dropped from the circuit. Errors if the kernel has no output channels, or if the bias or batch
+/// norm parameters don't have one value per output channel.
+/// Arguments
+///
+/// * `kernel` - the convolution kernel.
+/// * `bias` - the convolution bias.
+/// * `bn_scale` - the per-channel multiplier of the batch norm, i.e. `gamma / sqrt(var + eps)`.
+/// * `bn_shift` - the per-channel offset of the batch norm, i.e. `beta - mean * gamma / sqrt(var + eps)`.
+/// ```
+/// use ezkl::graph::utilities::fold_bn_into_conv;
+/// use ezkl::tensor::ops::{add_channel_bias, convolution, mul_channel_scale};
+/// use ezkl::tensor::Tensor;
+/// let x = Tensor::<f32>::new(Some(&[1.0, 2.0, -1.0, 0.5, 3.0, -2.0, 1.5, 0.0, 2.0]), &[1, 3, 3]).unwrap();
+/// let kernel = Tensor::<f32>::new(Some(&[0.5, 1.0, -1.0, 2.0, 1.0, 0.0, 0.25, -0.5]), &[2, 1, 2, 2]).unwrap();
+/// let bias = Tensor::<f32>::new(Some(&[1.0, -0.5]), &[2]).unwrap();
+/// let (bn_scale, bn_shift) = ([2.0, 0.5], [-1.0, 3.0]);
+///
+/// let conv = convolution(&vec![x.clone(), kernel.clone(), bias.clone()], (0, 0), (1, 1)).unwrap();
+/// let scale = Tensor::<f32>::new(Some(&bn_scale), &[2]).unwrap();
+/// let shift = Tensor::<f32>::new(Some(&bn_shift), &[2]).unwrap();
+/// let expected = add_channel_bias(&mul_channel_scale(&conv, &scale).unwrap(), &shift).unwrap();
+///
+/// let (folded_kernel, folded_bias) = fold_bn_into_conv(&kernel, &bias, &bn_scale, &bn_shift).unwrap();
+/// let folded = convolution(&vec![x, folded_kernel, folded_bias], (0, 0), (1, 1)).unwrap();
+/// assert_eq!(folded, expected);
+/// // the batch norm parameters must match the output channels
+/// assert!(fold_bn_into_conv(&kernel, &bias, &[2.0], &bn_shift).is_err());
+/// ```
+pub fn fold_bn_into_conv(
+    kernel: &Tensor<f32>,
+    bias: &Tensor<f32>,
+    bn_scale: &[f32],
+    bn_shift: &[f32],
+) -> Result<(Tensor<f32>, Tensor<f32>), TensorError> {
+    let output_channels = kernel.dims().first().copied().unwrap_or(0);
+    if output_channels == 0
+        || bias.len() != output_channels
+        || bn_scale.len() != output_channels
+        || bn_shift.len() != output_channels
+    {
+        return Err(TensorError::DimMismatch("fold_bn_into_conv".to_string()));
+    }
+
+    // number of kernel elements per output channel
+    let channel_size = kernel.len() / output_channels;
+    let mut folded_kernel = kernel.clone();
+    for i in 0..folded_kernel.len() {
+        folded_kernel[i] *= bn_scale[i / channel_size];
+    }
+    let mut folded_bias = bias.clone();
+    for (b, (scale, shift)) in folded_bias
+        .iter_mut()
+        .zip(bn_scale.iter().zip(bn_shift.iter()))
+    {
+        *b = *b * scale + shift;
+    }
+    Ok((folded_kernel, folded_bias))
+}
+
+/// Solves the linear system `a x = b` for `x` using Gaussian elimination with partial pivoting.
+/// Intended for small systems arising during quantization calibration: this is computed outside of the circuit in
+/// floating point and is not constrained.
+/// Arguments
+///
+/// * `a` - the square `n x n` coefficient matrix.
+/// * `b` - the right hand side, of shape `[n]` or `[n, m]`. `x` has the same shape.
+/// ```
+/// use ezkl::graph::utilities::solve;
+/// use ezkl::tensor::Tensor;
+/// // 2x + y = 5, x - y = 1
+/// let a = Tensor::<f32>::new(Some(&[2.0, 1.0, 1.0, -1.0]), &[2, 2]).unwrap();
+/// let b = Tensor::<f32>::new(Some(&[5.0, 1.0]), &[2]).unwrap();
+/// let x = solve(&a, &b).unwrap();
+/// assert!((x[0] - 2.0).abs() < 1e-6 && (x[1] - 1.0).abs() < 1e-6);
+/// ```
+pub fn solve(a: &Tensor<f32>, b: &Tensor<f32>) -> Result<Tensor<f32>, TensorError> {
+    if a.dims().len() != 2
+        || a.dims()[0] != a.dims()[1]
+        || b.dims().is_empty()
+        || b.dims().len() > 2
+        || b.dims()[0] != a.dims()[0]
+    {
+        return Err(TensorError::DimMismatch("solve".to_string()));
+    }
+    let n = a.dims()[0];
+    let m = if b.dims().len() == 2 { b.dims()[1] } else { 1 };
+    // augmented matrix [a | b], one row per equation
+    let mut rows: Vec<Vec<f32>> = (0..n)
+        .map(|i| {
+            let mut row = a[i * n..(i + 1) * n].to_vec();
+            row.extend_from_slice(&b[i * m..(i + 1) * m]);
+            row
+        })
+        .collect();
+
+    for col in 0..n {
+        // pick the largest pivot for numerical stability
+        let pivot = (col..n)
+            .max_by(|x, y| rows[*x][col].abs().total_cmp(&rows[*y][col].abs()))
+            .unwrap();
+        if rows[pivot][col].abs() < f32::EPSILON {
+            return Err(TensorError::Singular("solve".to_string()));
+        }
+        rows.swap(col, pivot);
+        for r in 0..n {
+            if r != col {
+                let factor = rows[r][col] / rows[col][col];
+                for c in col..(n + m) {
+                    rows[r][c] -= factor * rows[col][c];
+                }
+            }
+        }
+    }
+
+    let x: Vec<f32> = rows
         .iter()
-        .map(|e| (mult * e + shift).round() as i32)
+        .enumerate()
+        .flat_map(|(i, row)| row[n..].iter().map(move |e| e / row[i]))
         .collect();
-    Tensor::new(Some(&scaled), dims)
+    Tensor::new(Some(&x), b.dims())
 }
 
 /// Converts a scale (log base 2) to a fixed point multiplier.
@@ -30,6 +373,11 @@ pub fn scale_to_multiplier(scale: i32) -> f32 {
     i32::pow(2, scale as u32) as f32
 }
 
+/// Converts a scale (log base 2) to a double precision fixed point multiplier.
+pub fn scale_to_multiplier_f64(scale: i32) -> f64 {
+    f64::powi(2.0, scale)
+}
+
 /// Gets the shape of a onnx node's outlets.
 pub fn node_output_shapes(
     node: &Node<InferenceFact, Box<dyn InferenceOp>>,
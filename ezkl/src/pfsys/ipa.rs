@@ -0,0 +1,67 @@
+use super::check_instance_lens;
+use halo2_proofs::plonk::{create_proof, verify_proof, Circuit, Error, ProvingKey, VerifyingKey};
+use halo2_proofs::poly::ipa::commitment::{IPACommitmentScheme, ParamsIPA};
+use halo2_proofs::poly::ipa::multiopen::{ProverIPA, VerifierIPA};
+use halo2_proofs::poly::ipa::strategy::SingleStrategy;
+use halo2_proofs::poly::VerificationStrategy;
+use halo2_proofs::transcript::{
+    Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+};
+use halo2curves::pasta::{vesta, Fp};
+use rand::rngs::OsRng;
+use std::error::Error as StdError;
+use std::ops::Deref;
+
+/// Proves `circuit` with IPA over the vesta curve and a Blake2b transcript, returning the proof bytes.
+/// # Arguments
+/// * `params` - The IPA parameters.
+/// * `pk` - The proving key for the circuit.
+/// * `circuit` - The circuit, with its witness.
+/// * `instances` - The values of each of the circuit's instance columns.
+pub fn prove<C: Circuit<Fp>>(
+    params: &ParamsIPA<vesta::Affine>,
+    pk: &ProvingKey<vesta::Affine>,
+    circuit: C,
+    instances: &[Vec<Fp>],
+) -> Result<Vec<u8>, Error> {
+    let pi_inner = instances.iter().map(|e| e.deref()).collect::<Vec<&[Fp]>>();
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<IPACommitmentScheme<_>, ProverIPA<_>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[&pi_inner],
+        OsRng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+/// Verifies proof bytes produced by [prove], setting up the matching transcript and verification strategy.
+/// # Arguments
+/// * `params` - The IPA parameters.
+/// * `vk` - The verifying key for the circuit.
+/// * `instances` - The values of each of the circuit's instance columns.
+/// * `num_instance` - The number of values the circuit constrains each instance column to, checked against `instances`
+/// (see [check_instance_lens]) before verifying.
+/// * `proof` - The proof bytes.
+pub fn verify(
+    params: &ParamsIPA<vesta::Affine>,
+    vk: &VerifyingKey<vesta::Affine>,
+    instances: &[Vec<Fp>],
+    num_instance: &[usize],
+    proof: &[u8],
+) -> Result<(), Box<dyn StdError>> {
+    check_instance_lens(instances, num_instance)?;
+    let pi_inner = instances.iter().map(|e| e.deref()).collect::<Vec<&[Fp]>>();
+    let strategy = SingleStrategy::new(params);
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+    verify_proof::<IPACommitmentScheme<_>, VerifierIPA<_>, _, _, _>(
+        params,
+        vk,
+        strategy,
+        &[&pi_inner],
+        &mut transcript,
+    )?;
+    Ok(())
+}
@@ -1,37 +1,45 @@
 /// EVM related proving and verification
 pub mod evm;
+/// Proving and verification with IPA over the Pasta curves
+pub mod ipa;
 
+use crate::circuit::CircuitError;
 use crate::commands::{data_path, Cli, RunArgs};
 use crate::execute::ExecutionError;
 use crate::fieldutils::i32_to_felt;
 use crate::graph::{utilities::vector_to_quantized, Model, ModelCircuit};
-use crate::tensor::{Tensor, TensorType};
+use crate::tensor::{Tensor, TensorType, ValTensor};
 use halo2_proofs::arithmetic::FieldExt;
-use halo2_proofs::circuit::Value;
+use halo2_proofs::circuit::{FloorPlanner, Layouter, Value};
 use halo2_proofs::dev::MockProver;
 use halo2_proofs::plonk::{
-    create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, ProvingKey, VerifyingKey,
+    create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, ConstraintSystem, ProvingKey,
+    VerifyingKey,
 };
 use halo2_proofs::poly::commitment::{CommitmentScheme, Params, ParamsProver, Prover, Verifier};
 use halo2_proofs::poly::kzg::commitment::ParamsKZG;
 use halo2_proofs::poly::VerificationStrategy;
-use halo2_proofs::transcript::{EncodedChallenge, TranscriptReadBuffer, TranscriptWriterBuffer};
+use halo2_proofs::transcript::{
+    EncodedChallenge, TranscriptReadBuffer, TranscriptWrite, TranscriptWriterBuffer,
+};
 use halo2curves::bn256::Bn256;
 use halo2curves::group::ff::PrimeField;
 use halo2curves::serde::SerdeObject;
 use halo2curves::CurveAffine;
 use log::{debug, info, trace};
 use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 use snark_verifier::system::halo2::{compile, Config};
 use snark_verifier::verifier::plonk::PlonkProtocol;
 use std::error::Error;
+use std::fmt;
 use std::fs::File;
 use std::io::{self, BufReader, BufWriter, Cursor, Read, Write};
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// The input tensor data and shape, and output data for the computational graph (model) as floats.
 /// For example, the input might be the image data for a neural network, and the output class scores.
@@ -181,6 +189,46 @@ impl<F: FieldExt + SerdeObject, C: CurveAffine> From<Snark<F, C>> for SnarkWitne
     }
 }
 
+/// Metrics for a generated proof and the circuit it proves, e.g. to compare circuit designs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofReport {
+    /// Length of the proof (the finalized transcript) in bytes.
+    pub proof_len: usize,
+    /// Number of polynomial constraints, summed over all the gates of the circuit.
+    pub num_constraints: usize,
+    /// Number of lookup arguments in the circuit.
+    pub num_lookups: usize,
+    /// Wall-clock time taken to generate the proof, including the mock proving and verification sanity checks.
+    pub prover_time: Duration,
+}
+
+impl ProofReport {
+    /// Builds a report for `snark`, reading the constraint counts off the constraint system of its [VerifyingKey].
+    pub fn new<F: FieldExt + SerdeObject, C: CurveAffine>(
+        snark: &Snark<F, C>,
+        vk: &VerifyingKey<C>,
+        prover_time: Duration,
+    ) -> Self {
+        let cs = vk.cs();
+        ProofReport {
+            proof_len: snark.proof.len(),
+            num_constraints: cs.gates().iter().map(|g| g.polynomials().len()).sum(),
+            num_lookups: cs.lookups().len(),
+            prover_time,
+        }
+    }
+}
+
+impl fmt::Display for ProofReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "proof bytes: {}, constraints: {}, lookups: {}, prover time: {:?}",
+            self.proof_len, self.num_constraints, self.num_lookups, self.prover_time
+        )
+    }
+}
+
 type CircuitInputs<F> = (ModelCircuit<F>, Vec<Vec<F>>);
 
 /// Initialize the model circuit and quantize the provided float inputs from the provided `ModelInput`.
@@ -257,6 +305,53 @@ pub fn gen_srs<Scheme: CommitmentScheme>(k: u32) -> Scheme::ParamsProver {
     Scheme::ParamsProver::new(k)
 }
 
+/// Wraps a [Circuit] such that it is laid out using the [FloorPlanner] `P` instead of its own.
+/// As the proving helpers are generic over the circuit, this lets us compare the row usage of floor planners
+/// (e.g. [halo2_proofs::circuit::SimpleFloorPlanner] and [halo2_proofs::circuit::floor_planner::V1]) on the same circuit.
+#[derive(Debug)]
+pub struct WithFloorPlanner<C, P: FloorPlanner> {
+    /// The wrapped circuit.
+    pub inner: C,
+    _marker: PhantomData<P>,
+}
+
+impl<C, P: FloorPlanner> WithFloorPlanner<C, P> {
+    /// Wraps `inner` such that it is laid out using `P`.
+    pub fn new(inner: C) -> Self {
+        WithFloorPlanner {
+            inner,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: Clone, P: FloorPlanner> Clone for WithFloorPlanner<C, P> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<F: FieldExt, C: Circuit<F>, P: FloorPlanner> Circuit<F> for WithFloorPlanner<C, P> {
+    type Config = C::Config;
+    type FloorPlanner = P;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.inner.without_witnesses())
+    }
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure(cs)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        layouter: impl Layouter<F>,
+    ) -> Result<(), halo2_proofs::plonk::Error> {
+        self.inner.synthesize(config, layouter)
+    }
+}
+
 /// Creates a [VerifyingKey] and [ProvingKey] for a [ModelCircuit] (`circuit`) with specific [CommitmentScheme] parameters (`params`).
 pub fn create_keys<Scheme: CommitmentScheme, F: FieldExt + TensorType, C: Circuit<F>>(
     circuit: &C,
@@ -279,7 +374,8 @@ where
     Ok(pk)
 }
 
-/// a wrapper around halo2's create_proof
+/// a wrapper around halo2's create_proof.
+/// Generic over the [CommitmentScheme], such that it can be instantiated with KZG over BN256 (as in the cli) or IPA over the Pasta curves.
 pub fn create_proof_circuit<
     'params,
     Scheme: CommitmentScheme,
@@ -303,18 +399,70 @@ where
     Scheme::ParamsVerifier: 'params,
     Scheme::Scalar: SerdeObject,
 {
-    // quickly mock prove as a sanity check
-    {
-        debug!("running mock prover");
-        let prover = MockProver::run(params.k(), &circuit, instances.clone())
-            .map_err(Box::<dyn Error>::from)?;
-        prover
-            .verify()
-            .map_err(|e| Box::<dyn Error>::from(ExecutionError::VerifyError(e)))?;
-    }
+    create_proof_circuit_with_rng::<Scheme, F, C, P, V, Strategy, E, TW, TR, _>(
+        circuit, instances, params, pk, strategy, OsRng,
+    )
+}
 
-    let mut transcript = TranscriptWriterBuffer::<_, Scheme::Curve, _>::init(vec![]);
-    let mut rng = OsRng;
+/// a wrapper around halo2's create_proof which uses a caller-provided source of randomness.
+/// Passing a seeded rng (e.g. [rand::rngs::StdRng]) produces byte-identical proofs across runs, which is useful for testing.
+pub fn create_proof_circuit_with_rng<
+    'params,
+    Scheme: CommitmentScheme,
+    F: FieldExt + TensorType,
+    C: Circuit<F>,
+    P: Prover<'params, Scheme>,
+    V: Verifier<'params, Scheme>,
+    Strategy: VerificationStrategy<'params, Scheme, V>,
+    E: EncodedChallenge<Scheme::Curve>,
+    TW: TranscriptWriterBuffer<Vec<u8>, Scheme::Curve, E>,
+    TR: TranscriptReadBuffer<Cursor<Vec<u8>>, Scheme::Curve, E>,
+    R: RngCore + CryptoRng,
+>(
+    circuit: C,
+    instances: Vec<Vec<Scheme::Scalar>>,
+    params: &'params Scheme::ParamsProver,
+    pk: &ProvingKey<Scheme::Curve>,
+    strategy: Strategy,
+    rng: R,
+) -> Result<Snark<Scheme::Scalar, Scheme::Curve>, Box<dyn Error>>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::ParamsVerifier: 'params,
+    Scheme::Scalar: SerdeObject,
+{
+    prove_and_verify_circuit::<Scheme, F, C, P, V, Strategy, E, TW, TR, R>(
+        circuit, instances, params, pk, strategy, rng,
+    )
+    .map_err(|e| -> Box<dyn Error> { e })
+}
+
+// as create_proof_circuit_with_rng, but with errors which can be carried across threads
+fn prove_and_verify_circuit<
+    'params,
+    Scheme: CommitmentScheme,
+    F: FieldExt + TensorType,
+    C: Circuit<F>,
+    P: Prover<'params, Scheme>,
+    V: Verifier<'params, Scheme>,
+    Strategy: VerificationStrategy<'params, Scheme, V>,
+    E: EncodedChallenge<Scheme::Curve>,
+    TW: TranscriptWriterBuffer<Vec<u8>, Scheme::Curve, E>,
+    TR: TranscriptReadBuffer<Cursor<Vec<u8>>, Scheme::Curve, E>,
+    R: RngCore + CryptoRng,
+>(
+    circuit: C,
+    instances: Vec<Vec<Scheme::Scalar>>,
+    params: &'params Scheme::ParamsProver,
+    pk: &ProvingKey<Scheme::Curve>,
+    strategy: Strategy,
+    rng: R,
+) -> Result<Snark<Scheme::Scalar, Scheme::Curve>, Box<dyn Error + Send + Sync>>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::ParamsVerifier: 'params,
+    Scheme::Scalar: SerdeObject,
+{
     let number_instance = instances.iter().map(|x| x.len()).collect();
     trace!("number_instance {:?}", number_instance);
     let protocol = compile(
@@ -323,24 +471,16 @@ where
         Config::kzg().with_num_instance(number_instance),
     );
 
-    let pi_inner = instances
-        .iter()
-        .map(|e| e.deref())
-        .collect::<Vec<&[Scheme::Scalar]>>();
-    let pi_inner: &[&[&[Scheme::Scalar]]] = &[&pi_inner];
-    trace!("instances {:?}", instances);
-
-    let now = Instant::now();
-    create_proof::<Scheme, P, _, _, TW, _>(
+    let mut transcript = TW::init(vec![]);
+    mock_and_create_proof::<Scheme, C, P, E, TW, R>(
+        circuit,
+        &instances,
         params,
         pk,
-        &[circuit],
-        pi_inner,
-        &mut rng,
         &mut transcript,
+        rng,
     )?;
     let proof = transcript.finalize();
-    info!("Proof took {}", now.elapsed().as_secs());
 
     let checkable_pf = Snark::new(protocol, instances, proof);
 
@@ -359,7 +499,276 @@ where
     Ok(checkable_pf)
 }
 
-/// A wrapper around halo2's verify_proof
+/// As [create_proof_circuit], but also returns a [ProofReport] describing the proof and the circuit.
+pub fn create_proof_circuit_with_report<
+    'params,
+    Scheme: CommitmentScheme,
+    F: FieldExt + TensorType,
+    C: Circuit<F>,
+    P: Prover<'params, Scheme>,
+    V: Verifier<'params, Scheme>,
+    Strategy: VerificationStrategy<'params, Scheme, V>,
+    E: EncodedChallenge<Scheme::Curve>,
+    TW: TranscriptWriterBuffer<Vec<u8>, Scheme::Curve, E>,
+    TR: TranscriptReadBuffer<Cursor<Vec<u8>>, Scheme::Curve, E>,
+>(
+    circuit: C,
+    instances: Vec<Vec<Scheme::Scalar>>,
+    params: &'params Scheme::ParamsProver,
+    pk: &ProvingKey<Scheme::Curve>,
+    strategy: Strategy,
+) -> Result<(Snark<Scheme::Scalar, Scheme::Curve>, ProofReport), Box<dyn Error>>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::ParamsVerifier: 'params,
+    Scheme::Scalar: SerdeObject,
+{
+    let now = Instant::now();
+    let snark = create_proof_circuit::<Scheme, F, C, P, V, Strategy, E, TW, TR>(
+        circuit, instances, params, pk, strategy,
+    )?;
+    let report = ProofReport::new(&snark, pk.get_vk(), now.elapsed());
+    info!("{}", report);
+    Ok((snark, report))
+}
+
+/// Proves a batch of inputs (e.g. many MNIST images) against a single proving key, such that the (expensive) keygen
+/// only happens once. `circuit_for` builds the circuit for each input alongside its public instances, which are
+/// proved (and verified) as in [create_proof_circuit_with_rng], in order, drawing randomness from `rng`. See
+/// [create_proof_circuits_par] to prove the batch in parallel.
+pub fn create_proof_circuits<
+    'params,
+    Scheme: CommitmentScheme,
+    F: FieldExt + TensorType,
+    C: Circuit<F>,
+    P: Prover<'params, Scheme>,
+    V: Verifier<'params, Scheme>,
+    Strategy: VerificationStrategy<'params, Scheme, V> + Clone,
+    E: EncodedChallenge<Scheme::Curve>,
+    TW: TranscriptWriterBuffer<Vec<u8>, Scheme::Curve, E>,
+    TR: TranscriptReadBuffer<Cursor<Vec<u8>>, Scheme::Curve, E>,
+    B: Fn(&ValTensor<F>) -> (C, Vec<Vec<Scheme::Scalar>>),
+    R: RngCore + CryptoRng,
+>(
+    inputs: &[ValTensor<F>],
+    circuit_for: B,
+    params: &'params Scheme::ParamsProver,
+    pk: &ProvingKey<Scheme::Curve>,
+    strategy: Strategy,
+    mut rng: R,
+) -> Result<Vec<Snark<Scheme::Scalar, Scheme::Curve>>, Box<dyn Error + Send + Sync>>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::ParamsVerifier: 'params,
+    Scheme::Scalar: SerdeObject,
+{
+    inputs
+        .iter()
+        .map(|input| {
+            let (circuit, instances) = circuit_for(input);
+            prove_and_verify_circuit::<Scheme, F, C, P, V, Strategy, E, TW, TR, _>(
+                circuit,
+                instances,
+                params,
+                pk,
+                strategy.clone(),
+                &mut rng,
+            )
+        })
+        .collect()
+}
+
+/// As [create_proof_circuits], but proves the circuits of the batch in parallel using rayon. The circuits are built
+/// up front, and the returned proofs are in the same order as `inputs`. As an rng can't be shared across threads,
+/// `rng_for` creates the rng each proof draws from given the index of its input, e.g. to seed it.
+#[cfg(feature = "rayon")]
+pub fn create_proof_circuits_par<
+    'params,
+    Scheme: CommitmentScheme,
+    F: FieldExt + TensorType,
+    C: Circuit<F>,
+    P: Prover<'params, Scheme>,
+    V: Verifier<'params, Scheme>,
+    Strategy: VerificationStrategy<'params, Scheme, V> + Clone,
+    E: EncodedChallenge<Scheme::Curve>,
+    TW: TranscriptWriterBuffer<Vec<u8>, Scheme::Curve, E>,
+    TR: TranscriptReadBuffer<Cursor<Vec<u8>>, Scheme::Curve, E>,
+    B: Fn(&ValTensor<F>) -> (C, Vec<Vec<Scheme::Scalar>>),
+    R: RngCore + CryptoRng,
+    RF: Fn(usize) -> R + Sync,
+>(
+    inputs: &[ValTensor<F>],
+    circuit_for: B,
+    params: &'params Scheme::ParamsProver,
+    pk: &ProvingKey<Scheme::Curve>,
+    strategy: Strategy,
+    rng_for: RF,
+) -> Result<Vec<Snark<Scheme::Scalar, Scheme::Curve>>, Box<dyn Error + Send + Sync>>
+where
+    C: Circuit<Scheme::Scalar> + Send,
+    Scheme::ParamsProver: Sync,
+    Scheme::ParamsVerifier: 'params,
+    Scheme::Scalar: SerdeObject,
+    Strategy: Sync,
+{
+    use rayon::prelude::*;
+
+    let circuits = inputs.iter().map(circuit_for).collect::<Vec<_>>();
+    circuits
+        .into_par_iter()
+        .enumerate()
+        .map(|(i, (circuit, instances))| {
+            prove_and_verify_circuit::<Scheme, F, C, P, V, Strategy, E, TW, TR, _>(
+                circuit,
+                instances,
+                params,
+                pk,
+                strategy.clone(),
+                rng_for(i),
+            )
+        })
+        .collect()
+}
+
+/// As [create_proof_circuit_with_rng], but streams the proof bytes straight into `writer` (e.g. a [BufWriter] over a
+/// [File]) as the transcript is written, rather than collecting them into a [Snark] in memory. Returns the writer once
+/// the proof has been written and flushed. Unlike [create_proof_circuit_with_rng] the proof is not verified before
+/// returning, use [verify_proof_from_reader] to do so.
+pub fn create_proof_circuit_to_writer<
+    'params,
+    Scheme: CommitmentScheme,
+    F: FieldExt + TensorType,
+    C: Circuit<F>,
+    P: Prover<'params, Scheme>,
+    E: EncodedChallenge<Scheme::Curve>,
+    W: Write,
+    TW: TranscriptWriterBuffer<W, Scheme::Curve, E>,
+    R: RngCore + CryptoRng,
+>(
+    circuit: C,
+    instances: Vec<Vec<Scheme::Scalar>>,
+    params: &'params Scheme::ParamsProver,
+    pk: &ProvingKey<Scheme::Curve>,
+    writer: W,
+    rng: R,
+) -> Result<W, Box<dyn Error + Send + Sync>>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::ParamsVerifier: 'params,
+{
+    let mut transcript = TW::init(writer);
+    mock_and_create_proof::<Scheme, C, P, E, TW, R>(
+        circuit,
+        &instances,
+        params,
+        pk,
+        &mut transcript,
+        rng,
+    )?;
+    let mut writer = transcript.finalize();
+    writer.flush()?;
+
+    Ok(writer)
+}
+
+// mock proves `circuit` as a sanity check, then writes its proof into `transcript`
+fn mock_and_create_proof<
+    'params,
+    Scheme: CommitmentScheme,
+    C: Circuit<Scheme::Scalar>,
+    P: Prover<'params, Scheme>,
+    E: EncodedChallenge<Scheme::Curve>,
+    T: TranscriptWrite<Scheme::Curve, E>,
+    R: RngCore + CryptoRng,
+>(
+    circuit: C,
+    instances: &[Vec<Scheme::Scalar>],
+    params: &'params Scheme::ParamsProver,
+    pk: &ProvingKey<Scheme::Curve>,
+    transcript: &mut T,
+    mut rng: R,
+) -> Result<(), Box<dyn Error + Send + Sync>>
+where
+    Scheme::ParamsVerifier: 'params,
+{
+    {
+        debug!("running mock prover");
+        let prover = MockProver::run(params.k(), &circuit, instances.to_vec())?;
+        prover.verify().map_err(ExecutionError::VerifyError)?;
+    }
+
+    let pi_inner = instances
+        .iter()
+        .map(|e| e.deref())
+        .collect::<Vec<&[Scheme::Scalar]>>();
+    let pi_inner: &[&[&[Scheme::Scalar]]] = &[&pi_inner];
+    trace!("instances {:?}", instances);
+
+    let now = Instant::now();
+    create_proof::<Scheme, P, _, _, T, _>(params, pk, &[circuit], pi_inner, &mut rng, transcript)?;
+    info!("Proof took {}", now.elapsed().as_secs());
+    Ok(())
+}
+
+/// Checks that each of `instances` holds as many values as the circuit constrains the matching instance column to, as
+/// given by `num_instance`, such that a malformed instance fails with a clear [CircuitError::InstanceLenMismatch] rather
+/// than an opaque verification failure. A missing (or extra) column counts as holding (or expecting) no values.
+pub fn check_instance_lens<F>(
+    instances: &[Vec<F>],
+    num_instance: &[usize],
+) -> Result<(), CircuitError> {
+    for i in 0..instances.len().max(num_instance.len()) {
+        let expected = num_instance.get(i).copied().unwrap_or(0);
+        let got = instances.get(i).map_or(0, Vec::len);
+        if expected != got {
+            return Err(CircuitError::InstanceLenMismatch { expected, got });
+        }
+    }
+    Ok(())
+}
+
+/// As [verify_proof_circuit], but reads the proof bytes from `reader` (e.g. a [BufReader] over a [File] written by
+/// [create_proof_circuit_to_writer]) rather than from a [Snark] held in memory. `instances` are first checked against
+/// `num_instance`, the number of values the circuit constrains each instance column to, see [check_instance_lens].
+pub fn verify_proof_from_reader<
+    'params,
+    F: FieldExt,
+    V: Verifier<'params, Scheme>,
+    Scheme: CommitmentScheme,
+    Strategy: VerificationStrategy<'params, Scheme, V>,
+    E: EncodedChallenge<Scheme::Curve>,
+    Rd: Read,
+    TR: TranscriptReadBuffer<Rd, Scheme::Curve, E>,
+>(
+    reader: Rd,
+    instances: &[Vec<Scheme::Scalar>],
+    num_instance: &[usize],
+    params: &'params Scheme::ParamsVerifier,
+    vk: &VerifyingKey<Scheme::Curve>,
+    strategy: Strategy,
+) -> Result<Strategy::Output, Box<dyn Error + Send + Sync>> {
+    check_instance_lens(instances, num_instance)?;
+
+    let pi_inner = instances
+        .iter()
+        .map(|e| e.deref())
+        .collect::<Vec<&[Scheme::Scalar]>>();
+    let instances: &[&[&[Scheme::Scalar]]] = &[&pi_inner];
+    trace!("instances {:?}", instances);
+
+    let mut transcript = TR::init(reader);
+    Ok(verify_proof::<Scheme, V, _, TR, _>(
+        params,
+        vk,
+        strategy,
+        instances,
+        &mut transcript,
+    )?)
+}
+
+/// A wrapper around halo2's verify_proof, generic over the [CommitmentScheme]. If the [Snark] holds its protocol (as
+/// when created by [create_proof_circuit], or loaded with params and a vk) its instances are first checked against the
+/// protocol's instance column lengths, see [check_instance_lens].
 pub fn verify_proof_circuit<
     'params,
     F: FieldExt,
@@ -373,10 +782,14 @@ pub fn verify_proof_circuit<
     params: &'params Scheme::ParamsVerifier,
     vk: &VerifyingKey<Scheme::Curve>,
     strategy: Strategy,
-) -> Result<Strategy::Output, halo2_proofs::plonk::Error>
+) -> Result<Strategy::Output, Box<dyn Error + Send + Sync>>
 where
     Scheme::Scalar: SerdeObject,
 {
+    if let Some(protocol) = &snark.protocol {
+        check_instance_lens(&snark.instances, &protocol.num_instance)?;
+    }
+
     let pi_inner = snark
         .instances
         .iter()
@@ -388,7 +801,13 @@ where
     let now = Instant::now();
     let mut transcript = TranscriptReadBuffer::init(Cursor::new(snark.proof.clone()));
     info!("verify took {}", now.elapsed().as_secs());
-    verify_proof::<Scheme, V, _, TR, _>(params, vk, strategy, instances, &mut transcript)
+    Ok(verify_proof::<Scheme, V, _, TR, _>(
+        params,
+        vk,
+        strategy,
+        instances,
+        &mut transcript,
+    )?)
 }
 
 /// Loads a [VerifyingKey] at `path`.
@@ -506,9 +925,538 @@ mod tests {
     use std::io::copy;
 
     use super::*;
+    use crate::circuit::polynomial::{Config as PolyConfig, InputType, Node as PolyNode, Op};
+    use crate::tensor::ops::affine;
+    use crate::tensor::{ValTensor, VarTensor};
+    use halo2_proofs::circuit::{floor_planner::V1, SimpleFloorPlanner};
+    use halo2_proofs::plonk::{Column, Error as PlonkError, Instance};
+    use halo2_proofs::poly::ipa::commitment::ParamsIPA;
     use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+    use halo2_proofs::poly::kzg::multiopen::{ProverGWC, VerifierGWC};
+    use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+    use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255};
+    use halo2curves::bn256::{Fr, G1Affine};
+    use halo2curves::pasta::{vesta, Fp as PastaFp};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use tempfile::Builder;
 
+    const K: u32 = 5;
+    const LEN: usize = 2;
+    const KERNEL: [i32; LEN * LEN] = [3, -1, 2, 4];
+    const BIAS: [i32; LEN] = [0, 5];
+
+    #[derive(Clone)]
+    struct AffineCircuit<F: FieldExt + TensorType> {
+        input: ValTensor<F>,
+        params: [ValTensor<F>; 2],
+    }
+
+    impl<F: FieldExt + TensorType> Circuit<F> for AffineCircuit<F> {
+        type Config = PolyConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            AffineCircuit {
+                input: ValTensor::unknown(self.input.dims()),
+                params: [
+                    ValTensor::unknown(self.params[0].dims()),
+                    ValTensor::unknown(self.params[1].dims()),
+                ],
+            }
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = VarTensor::new_advice(cs, K as usize, LEN, vec![LEN], true, 512);
+            let kernel =
+                VarTensor::new_advice(cs, K as usize, LEN * LEN, vec![LEN, LEN], true, 512);
+            let bias = VarTensor::new_advice(cs, K as usize, LEN, vec![LEN], true, 512);
+            let output = VarTensor::new_advice(cs, K as usize, LEN, vec![LEN], true, 512);
+            let affine_node = PolyNode {
+                op: Op::Affine,
+                input_order: vec![
+                    InputType::Input(0),
+                    InputType::Input(1),
+                    InputType::Input(2),
+                ],
+            };
+            Self::Config::configure(cs, &[input, kernel, bias], &output, &[affine_node])
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), PlonkError> {
+            config
+                .layout(
+                    &mut layouter,
+                    &[
+                        self.input.clone(),
+                        self.params[0].clone(),
+                        self.params[1].clone(),
+                    ],
+                )
+                .map_err(|_| PlonkError::Synthesis)?;
+            Ok(())
+        }
+    }
+
+    fn affine_circuit() -> AffineCircuit<Fr> {
+        affine_circuit_over()
+    }
+
+    fn affine_circuit_over<F: FieldExt + TensorType>() -> AffineCircuit<F> {
+        affine_circuit_with_input(&[1, 2])
+    }
+
+    fn affine_circuit_with_input<F: FieldExt + TensorType>(input: &[i32]) -> AffineCircuit<F> {
+        let to_val = |v: &[i32], dims: &[usize]| -> ValTensor<F> {
+            let t: Tensor<Value<F>> = Tensor::new(Some(v), dims).unwrap().into();
+            t.into()
+        };
+        AffineCircuit {
+            input: to_val(input, &[LEN]),
+            params: [to_val(&KERNEL, &[LEN, LEN]), to_val(&BIAS, &[LEN])],
+        }
+    }
+
+    fn prove_seeded(
+        params: &ParamsKZG<Bn256>,
+        pk: &ProvingKey<G1Affine>,
+        seed: u64,
+    ) -> Snark<Fr, G1Affine> {
+        create_proof_circuit_with_rng::<
+            KZGCommitmentScheme<_>,
+            Fr,
+            _,
+            ProverGWC<_>,
+            VerifierGWC<_>,
+            _,
+            Challenge255<_>,
+            Blake2bWrite<_, _, _>,
+            Blake2bRead<_, _, _>,
+            _,
+        >(
+            affine_circuit(),
+            vec![],
+            params,
+            pk,
+            SingleStrategy::new(params),
+            StdRng::seed_from_u64(seed),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_seeded_proofs_are_reproducible() {
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, Fr, AffineCircuit<Fr>>(
+            &affine_circuit(),
+            &params,
+        )
+        .unwrap();
+
+        // both proofs are verified by the helper before being returned
+        let first = prove_seeded(&params, &pk, 42);
+        let second = prove_seeded(&params, &pk, 42);
+        assert_eq!(first.proof, second.proof);
+
+        let other = prove_seeded(&params, &pk, 7);
+        assert_ne!(first.proof, other.proof);
+    }
+
+    #[test]
+    fn test_kzg_bn256_proof_verifies() {
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, Fr, AffineCircuit<Fr>>(
+            &affine_circuit(),
+            &params,
+        )
+        .unwrap();
+
+        let snark = create_proof_circuit::<
+            KZGCommitmentScheme<_>,
+            Fr,
+            _,
+            ProverGWC<_>,
+            VerifierGWC<_>,
+            _,
+            Challenge255<_>,
+            Blake2bWrite<_, _, _>,
+            Blake2bRead<_, _, _>,
+        >(
+            affine_circuit(),
+            vec![],
+            &params,
+            &pk,
+            SingleStrategy::new(&params),
+        )
+        .unwrap();
+
+        let verifier_params = params.verifier_params();
+        let res = verify_proof_circuit::<
+            Fr,
+            VerifierGWC<_>,
+            _,
+            _,
+            Challenge255<_>,
+            Blake2bRead<_, _, _>,
+        >(
+            &snark,
+            verifier_params,
+            pk.get_vk(),
+            SingleStrategy::new(verifier_params),
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_keys_from_unknown_values_verify_proofs() {
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        // a keygen-time circuit that never saw the witness
+        let keygen_circuit = AffineCircuit::<Fr> {
+            input: ValTensor::unknown(&[LEN]),
+            params: [ValTensor::unknown(&[LEN, LEN]), ValTensor::unknown(&[LEN])],
+        };
+        let vk = keygen_vk(&params, &keygen_circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &keygen_circuit).unwrap();
+
+        let snark = prove_seeded(&params, &pk, 42);
+
+        let verifier_params = params.verifier_params();
+        let res = verify_proof_circuit::<
+            Fr,
+            VerifierGWC<_>,
+            _,
+            _,
+            Challenge255<_>,
+            Blake2bRead<_, _, _>,
+        >(
+            &snark,
+            verifier_params,
+            pk.get_vk(),
+            SingleStrategy::new(verifier_params),
+        );
+        assert!(res.is_ok());
+    }
+
+    fn prove_with_floor_planner<P: FloorPlanner>() {
+        let circuit = WithFloorPlanner::<_, P>::new(affine_circuit());
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, Fr, _>(&circuit, &params).unwrap();
+
+        // the helper verifies the proof before returning it
+        let res = create_proof_circuit::<
+            KZGCommitmentScheme<_>,
+            Fr,
+            _,
+            ProverGWC<_>,
+            VerifierGWC<_>,
+            _,
+            Challenge255<_>,
+            Blake2bWrite<_, _, _>,
+            Blake2bRead<_, _, _>,
+        >(circuit, vec![], &params, &pk, SingleStrategy::new(&params));
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_proof_verifies_under_both_floor_planners() {
+        prove_with_floor_planner::<SimpleFloorPlanner>();
+        prove_with_floor_planner::<V1>();
+    }
+
+    #[test]
+    fn test_proof_report_matches_proof() {
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, Fr, AffineCircuit<Fr>>(
+            &affine_circuit(),
+            &params,
+        )
+        .unwrap();
+
+        let (snark, report) = create_proof_circuit_with_report::<
+            KZGCommitmentScheme<_>,
+            Fr,
+            _,
+            ProverGWC<_>,
+            VerifierGWC<_>,
+            _,
+            Challenge255<_>,
+            Blake2bWrite<_, _, _>,
+            Blake2bRead<_, _, _>,
+        >(
+            affine_circuit(),
+            vec![],
+            &params,
+            &pk,
+            SingleStrategy::new(&params),
+        )
+        .unwrap();
+        assert_eq!(report.proof_len, snark.proof.len());
+        // one constraint per output element of the affine gate
+        assert_eq!(report.num_constraints, LEN);
+        assert_eq!(report.num_lookups, 0);
+    }
+
+    /// An [AffineCircuit] whose output is constrained to its (single) instance column, such that each proof is tied to
+    /// its input.
+    #[derive(Clone)]
+    struct PublicAffineCircuit<F: FieldExt + TensorType>(AffineCircuit<F>);
+
+    impl<F: FieldExt + TensorType> Circuit<F> for PublicAffineCircuit<F> {
+        type Config = (PolyConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            PublicAffineCircuit(self.0.without_witnesses())
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let instance = cs.instance_column();
+            cs.enable_equality(instance);
+            (AffineCircuit::configure(cs), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (mut config, instance): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), PlonkError> {
+            let output = config
+                .layout(
+                    &mut layouter,
+                    &[
+                        self.0.input.clone(),
+                        self.0.params[0].clone(),
+                        self.0.params[1].clone(),
+                    ],
+                )
+                .map_err(|_| PlonkError::Synthesis)?;
+            match output {
+                ValTensor::PrevAssigned { inner, .. } => {
+                    inner.enum_map(|i, x| layouter.constrain_instance(x.cell(), instance, i))?;
+                    Ok(())
+                }
+                _ => Err(PlonkError::Synthesis),
+            }
+        }
+    }
+
+    const BATCH: [[i32; LEN]; 3] = [[1, 2], [-3, 0], [7, 5]];
+
+    fn batch_inputs() -> Vec<ValTensor<Fr>> {
+        BATCH
+            .iter()
+            .map(|input| affine_circuit_with_input::<Fr>(input).input)
+            .collect()
+    }
+
+    // each input is proved against the output of the affine layer over it
+    fn batch_circuit_for(input: &ValTensor<Fr>) -> (PublicAffineCircuit<Fr>, Vec<Vec<Fr>>) {
+        let mut circuit = affine_circuit();
+        circuit.input = input.clone();
+
+        let mut felts = vec![];
+        if let ValTensor::Value { inner, .. } = input {
+            for v in inner.iter().cloned() {
+                let _ = v.map(|f| felts.push(f));
+            }
+        }
+        let to_felts =
+            |v: &[i32], dims: &[usize]| Tensor::new(Some(v), dims).unwrap().map(i32_to_felt::<Fr>);
+        let output = affine(&vec![
+            Tensor::new(Some(&felts), &[LEN]).unwrap(),
+            to_felts(&KERNEL, &[LEN, LEN]),
+            to_felts(&BIAS, &[LEN]),
+        ])
+        .unwrap();
+
+        (PublicAffineCircuit(circuit), vec![output.to_vec()])
+    }
+
+    fn verify_kzg(
+        snark: &Snark<Fr, G1Affine>,
+        params: &ParamsKZG<Bn256>,
+        pk: &ProvingKey<G1Affine>,
+    ) -> bool {
+        let verifier_params = params.verifier_params();
+        verify_proof_circuit::<Fr, VerifierGWC<_>, _, _, Challenge255<_>, Blake2bRead<_, _, _>>(
+            snark,
+            verifier_params,
+            pk.get_vk(),
+            SingleStrategy::new(verifier_params),
+        )
+        .is_ok()
+    }
+
+    fn assert_batch_verifies(
+        snarks: &[Snark<Fr, G1Affine>],
+        params: &ParamsKZG<Bn256>,
+        pk: &ProvingKey<G1Affine>,
+    ) {
+        assert_eq!(snarks.len(), BATCH.len());
+        for (i, snark) in snarks.iter().enumerate() {
+            // proved against the output for its own input
+            assert_eq!(snark.instances, batch_circuit_for(&batch_inputs()[i]).1);
+            assert!(verify_kzg(snark, params, pk));
+
+            // and not against the output for another input
+            let other = &snarks[(i + 1) % snarks.len()];
+            let swapped = Snark {
+                protocol: None,
+                instances: other.instances.clone(),
+                proof: snark.proof.clone(),
+            };
+            assert!(!verify_kzg(&swapped, params, pk));
+        }
+    }
+
+    fn batch_keys() -> (ParamsKZG<Bn256>, ProvingKey<G1Affine>) {
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, Fr, PublicAffineCircuit<Fr>>(
+            &PublicAffineCircuit(affine_circuit()),
+            &params,
+        )
+        .unwrap();
+        (params, pk)
+    }
+
+    #[test]
+    fn test_batched_proofs_share_keys_and_verify() {
+        let (params, pk) = batch_keys();
+        let snarks = create_proof_circuits::<
+            KZGCommitmentScheme<_>,
+            Fr,
+            _,
+            ProverGWC<_>,
+            VerifierGWC<_>,
+            _,
+            Challenge255<_>,
+            Blake2bWrite<_, _, _>,
+            Blake2bRead<_, _, _>,
+            _,
+            _,
+        >(
+            &batch_inputs(),
+            batch_circuit_for,
+            &params,
+            &pk,
+            SingleStrategy::new(&params),
+            StdRng::seed_from_u64(0),
+        )
+        .unwrap();
+        assert_batch_verifies(&snarks, &params, &pk);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_batched_proofs_verify() {
+        let (params, pk) = batch_keys();
+        let snarks = create_proof_circuits_par::<
+            KZGCommitmentScheme<_>,
+            Fr,
+            _,
+            ProverGWC<_>,
+            VerifierGWC<_>,
+            _,
+            Challenge255<_>,
+            Blake2bWrite<_, _, _>,
+            Blake2bRead<_, _, _>,
+            _,
+            _,
+            _,
+        >(
+            &batch_inputs(),
+            batch_circuit_for,
+            &params,
+            &pk,
+            SingleStrategy::new(&params),
+            |i| StdRng::seed_from_u64(i as u64),
+        )
+        .unwrap();
+        assert_batch_verifies(&snarks, &params, &pk);
+    }
+
+    #[test]
+    fn test_proof_streams_to_and_from_file() {
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, Fr, AffineCircuit<Fr>>(
+            &affine_circuit(),
+            &params,
+        )
+        .unwrap();
+
+        let tmp_dir = Builder::new().prefix("example").tempdir().unwrap();
+        let fname = tmp_dir.path().join("proof.pf");
+        let writer = BufWriter::new(File::create(&fname).unwrap());
+        create_proof_circuit_to_writer::<
+            KZGCommitmentScheme<_>,
+            Fr,
+            _,
+            ProverGWC<_>,
+            Challenge255<_>,
+            _,
+            Blake2bWrite<_, _, _>,
+            _,
+        >(affine_circuit(), vec![], &params, &pk, writer, OsRng)
+        .unwrap();
+        assert!(std::fs::metadata(&fname).unwrap().len() > 0);
+
+        let reader = BufReader::new(File::open(&fname).unwrap());
+        let verifier_params = params.verifier_params();
+        let res = verify_proof_from_reader::<
+            Fr,
+            VerifierGWC<_>,
+            _,
+            _,
+            Challenge255<_>,
+            _,
+            Blake2bRead<_, _, _>,
+        >(
+            reader,
+            &[],
+            &[],
+            verifier_params,
+            pk.get_vk(),
+            SingleStrategy::new(verifier_params),
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_ipa_proof_verifies_from_bytes() {
+        let params: ParamsIPA<vesta::Affine> = ParamsIPA::new(K);
+        let circuit = affine_circuit_over::<PastaFp>();
+        let vk = keygen_vk(&params, &circuit.without_witnesses()).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit.without_witnesses()).unwrap();
+
+        let proof = ipa::prove(&params, &pk, circuit, &[]).unwrap();
+        assert!(ipa::verify(&params, pk.get_vk(), &[], &[], &proof).is_ok());
+
+        // the affine circuit has no instance column, so a stray instance is caught before verifying
+        let err = ipa::verify(
+            &params,
+            pk.get_vk(),
+            &[vec![PastaFp::from(1_u64)]],
+            &[],
+            &proof,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CircuitError>(),
+            Some(CircuitError::InstanceLenMismatch {
+                expected: 0,
+                got: 1
+            })
+        ));
+
+        let mut tampered = proof;
+        tampered[0] ^= 1;
+        assert!(ipa::verify(&params, pk.get_vk(), &[], &[], &tampered).is_err());
+    }
+
     #[tokio::test]
     async fn test_can_load_pre_generated_srs() {
         let tmp_dir = Builder::new().prefix("example").tempdir().unwrap();
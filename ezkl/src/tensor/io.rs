@@ -0,0 +1,48 @@
+use super::{Tensor, TensorError};
+use std::path::Path;
+
+/// Loads a [Tensor] of f32s from a headerless CSV file, reading the values row-major (row after row, left to right)
+/// and reshaping them to `dims`. Errors if the file can't be read or parsed, or if it doesn't hold exactly as many
+/// values as `dims`.
+/// # Arguments
+///
+/// * `path` - Path to the CSV file.
+/// * `dims` - The shape of the loaded tensor.
+pub fn load_csv<P: AsRef<Path>>(path: P, dims: &[usize]) -> Result<Tensor<f32>, TensorError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| TensorError::Load(e.to_string()))?;
+
+    let mut values = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|e| TensorError::Load(e.to_string()))?;
+        for field in record.iter() {
+            let value = field
+                .trim()
+                .parse::<f32>()
+                .map_err(|e| TensorError::Load(format!("{:?}: {}", field, e)))?;
+            values.push(value);
+        }
+    }
+    Tensor::new(Some(&values), dims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tabular.csv");
+
+    #[test]
+    fn load_csv_into_tensor() {
+        let t = load_csv(FIXTURE, &[2, 3]).unwrap();
+        let expected =
+            Tensor::<f32>::new(Some(&[1.0, 2.5, -3.0, 4.0, 0.25, -6.5]), &[2, 3]).unwrap();
+        assert_eq!(t, expected);
+        // the number of values must match the dims
+        assert!(load_csv(FIXTURE, &[2, 2]).is_err());
+        assert!(load_csv("missing.csv", &[2, 3]).is_err());
+    }
+}
@@ -1,3 +1,6 @@
+/// Loading tensors from files, e.g. tabular model inputs.
+#[cfg(feature = "csv")]
+pub mod io;
 /// Implementations of common operations on tensors.
 pub mod ops;
 /// A wrapper around a tensor of circuit variables / advices.
@@ -23,6 +26,7 @@ use std::fmt::Debug;
 use std::iter::Iterator;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::ops::Neg;
 use std::ops::Range;
 use thiserror::Error;
 /// A wrapper for tensor related errors.
@@ -37,9 +41,37 @@ pub enum TensorError {
     /// wrong method was called on a tensor-like struct
     #[error("wrong method called")]
     WrongMethod,
+    /// A value doesn't fit in the requested type
+    #[error("value out of range for the output type in tensor op: {0}")]
+    Overflow(String),
+    /// A matrix that needs to be inverted is singular
+    #[error("singular matrix in tensor op: {0}")]
+    Singular(String),
+    /// A tensor couldn't be read from a file
+    #[error("failed to load tensor: {0}")]
+    Load(String),
+    /// A float tensor holds a NaN or infinite value
+    #[error("non-finite value at index {0}")]
+    NotFinite(usize),
+    /// An exponent is zero, for which there is no generic multiplicative identity to return
+    #[error("zero exponent at index {0}")]
+    ZeroExponent(usize),
+    /// A divisor is zero
+    #[error("zero divisor at index {0}")]
+    ZeroDivisor(usize),
 }
 
 /// The (inner) type of tensor elements.
+///
+/// `bool` is a [TensorType], such that masks can be represented directly:
+/// ```
+/// use ezkl::tensor::{Tensor, TensorType};
+/// let x = Tensor::<i32>::new(Some(&[-2, 0, 3, -1]), &[2, 2]).unwrap();
+/// let mask = x.map(|e| e > 0);
+/// assert_eq!(mask, Tensor::new(Some(&[false, false, true, false]), &[2, 2]).unwrap());
+/// assert_eq!(bool::zero(), Some(false));
+/// assert_eq!(false.tmax(&true), Some(true));
+/// ```
 pub trait TensorType: Clone + Debug + 'static {
     /// Returns the zero value.
     fn zero() -> Option<Self> {
@@ -88,9 +120,33 @@ impl TensorType for f32 {
     }
 }
 
+impl TensorType for f64 {
+    fn zero() -> Option<Self> {
+        Some(0.0)
+    }
+
+    // as for f32, NAN values need to be handled explicitly.
+    fn tmax(&self, other: &Self) -> Option<Self> {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => Some(f64::NAN),
+            (true, false) => Some(*other),
+            (false, true) => Some(*self),
+            (false, false) => {
+                if self >= other {
+                    Some(*self)
+                } else {
+                    Some(*other)
+                }
+            }
+        }
+    }
+}
+
 tensor_type!(i32, Int32, 0);
+tensor_type!(i64, Int64, 0);
 tensor_type!(usize, USize, 0);
 tensor_type!((), Empty, ());
+tensor_type!(bool, Bool, false);
 
 impl<T: TensorType> TensorType for Tensor<T> {
     fn zero() -> Option<Self> {
@@ -173,8 +229,15 @@ impl TensorType for halo2curves::pasta::Fp {
         Some(halo2curves::pasta::Fp::zero())
     }
 
+    // field elements have no meaningful ordering of their own, so we compare their signed integer interpretation. Note
+    // that `felt_to_i32` reads any element above 65536 as negative, so the ordering is only correct for values in
+    // [-65536, 65536]: e.g. 65537 doesn't read back as itself, and isn't picked over 65536.
     fn tmax(&self, other: &Self) -> Option<Self> {
-        Some((*self).max(*other))
+        if felt_to_i32(*self) >= felt_to_i32(*other) {
+            Some(*self)
+        } else {
+            Some(*other)
+        }
     }
 }
 
@@ -183,18 +246,63 @@ impl TensorType for halo2curves::bn256::Fr {
         Some(halo2curves::bn256::Fr::zero())
     }
 
+    // as for Fp, compare the signed integer interpretation, which is only ordered correctly within [-65536, 65536]
     fn tmax(&self, other: &Self) -> Option<Self> {
-        Some((*self).max(*other))
+        if felt_to_i32(*self) >= felt_to_i32(*other) {
+            Some(*self)
+        } else {
+            Some(*other)
+        }
     }
 }
 
 /// A generic multi-dimensional array representation of a Tensor.
 /// The `inner` attribute contains a vector of values whereas `dims` corresponds to the dimensionality of the array
 /// and as such determines how we index, query for values, or slice a Tensor.
+/// `strides` holds the step in `inner` between consecutive elements along each dimension. The public constructors and
+/// operations currently always produce contiguous (row-major) tensors, such that `strides` is determined by `dims`;
+/// [Tensor::make_contiguous] materializes any other layout.
 #[derive(Clone, Debug, Eq)]
 pub struct Tensor<T: TensorType> {
     inner: Vec<T>,
     dims: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+/// Returns the row-major strides of a tensor of shape `dims`.
+fn contiguous_strides(dims: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; dims.len()];
+    for i in (0..dims.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * dims[i + 1];
+    }
+    strides
+}
+
+/// Checks that `axes` is a permutation of `0..rank`, i.e. that it names each axis of a rank `rank` tensor exactly once,
+/// as required to permute (transpose) a tensor's axes.
+/// ```
+/// use ezkl::tensor::validate_permutation;
+/// assert!(validate_permutation(&[0, 2, 3, 1], 4).is_ok());
+/// assert!(validate_permutation(&[0, 2, 2, 1], 4).is_err());
+/// ```
+pub fn validate_permutation(axes: &[usize], rank: usize) -> Result<(), TensorError> {
+    let err = || {
+        TensorError::DimMismatch(format!(
+            "permutation {:?} for a tensor of rank {}",
+            axes, rank
+        ))
+    };
+    if axes.len() != rank {
+        return Err(err());
+    }
+    let mut seen = vec![false; rank];
+    for a in axes {
+        match seen.get_mut(*a) {
+            Some(s) if !*s => *s = true,
+            _ => return Err(err()),
+        }
+    }
+    Ok(())
 }
 
 impl<T: TensorType> IntoIterator for Tensor<T> {
@@ -222,6 +330,13 @@ impl<T: TensorType> DerefMut for Tensor<T> {
     }
 }
 
+impl<T: TensorType + Neg<Output = T>> Neg for Tensor<T> {
+    type Output = Tensor<T>;
+    fn neg(self) -> Self::Output {
+        ops::negate(&self)
+    }
+}
+
 impl<T: PartialEq + TensorType> PartialEq for Tensor<T> {
     fn eq(&self, other: &Tensor<T>) -> bool {
         self.dims == other.dims && self.deref() == other.deref()
@@ -319,15 +434,66 @@ impl<T: Clone + TensorType> Tensor<T> {
                 Ok(Tensor {
                     inner: Vec::from(v),
                     dims: Vec::from(dims),
+                    strides: contiguous_strides(dims),
                 })
             }
             None => Ok(Tensor {
                 inner: vec![T::zero().unwrap(); total_dims],
                 dims: Vec::from(dims),
+                strides: contiguous_strides(dims),
             }),
         }
     }
 
+    /// Creates a new tensor of shape `dims` from an iterator, erroring if the iterator doesn't yield exactly as many
+    /// elements as `dims` holds. Stops consuming the iterator as soon as it yields one element too many.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::try_from_iter(0..6, &[2, 3]).unwrap();
+    /// assert_eq!(a, Tensor::<i32>::new(Some(&[0, 1, 2, 3, 4, 5]), &[2, 3]).unwrap());
+    /// ```
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(
+        iter: I,
+        dims: &[usize],
+    ) -> Result<Self, TensorError> {
+        let total_dims: usize = dims.iter().product();
+        let mut inner = Vec::with_capacity(total_dims);
+        for e in iter {
+            if inner.len() == total_dims {
+                return Err(TensorError::DimError);
+            }
+            inner.push(e);
+        }
+        if inner.len() != total_dims {
+            return Err(TensorError::DimError);
+        }
+        Ok(Tensor {
+            inner,
+            dims: Vec::from(dims),
+            strides: contiguous_strides(dims),
+        })
+    }
+
+    /// Stacks tensors which all share the same shape `S` into a single tensor of shape `[N, S...]`, e.g. to assemble a
+    /// batch of `[1, 28, 28]` images into a `[N, 1, 28, 28]` tensor. Errors if no tensors are passed or if any of their
+    /// shapes differ.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[1, 2]), &[1, 2]).unwrap();
+    /// let b = Tensor::<i32>::new(Some(&[3, 4]), &[1, 2]).unwrap();
+    /// let batch = Tensor::stack_batch(&[a, b]).unwrap();
+    /// assert_eq!(batch, Tensor::<i32>::new(Some(&[1, 2, 3, 4]), &[2, 1, 2]).unwrap());
+    /// ```
+    pub fn stack_batch(images: &[Tensor<T>]) -> Result<Self, TensorError> {
+        let first = images.first().ok_or(TensorError::DimError)?;
+        if images.iter().any(|image| image.dims() != first.dims()) {
+            return Err(TensorError::DimMismatch("stack_batch".to_string()));
+        }
+        let mut dims = vec![images.len()];
+        dims.extend(first.dims());
+        Tensor::try_from_iter(images.iter().flat_map(|image| image.iter().cloned()), &dims)
+    }
+
     /// Returns the number of elements in the tensor.
     pub fn len(&self) -> usize {
         self.dims().iter().product::<usize>()
@@ -337,6 +503,22 @@ impl<T: Clone + TensorType> Tensor<T> {
         self.dims().iter().product::<usize>() == 0
     }
 
+    /// Returns the single element of a tensor holding exactly one element, e.g. the output of [ops::dot] or
+    /// [ops::sum], erroring otherwise.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::dot;
+    /// let x = Tensor::<i32>::new(Some(&[1, 2, 3]), &[3]).unwrap();
+    /// assert_eq!(*dot(&vec![&x, &x]).unwrap().scalar().unwrap(), 14);
+    /// assert!(x.scalar().is_err());
+    /// ```
+    pub fn scalar(&self) -> Result<&T, TensorError> {
+        if self.len() != 1 {
+            return Err(TensorError::DimError);
+        }
+        Ok(&self[0])
+    }
+
     /// Set one single value on the tensor.
     ///
     /// ```
@@ -377,6 +559,17 @@ impl<T: Clone + TensorType> Tensor<T> {
     ///
     /// assert_eq!(a.get_slice(&[0..2]).unwrap(), b);
     /// ```
+    ///
+    /// A descending range `start..end` (with `start > end`) reads the same elements as `end..start` but in reverse
+    /// order, such that `3..0` reverses an axis of length 3.
+    ///
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap();
+    /// let b = Tensor::<i32>::new(Some(&[3, 2, 1, 6, 5, 4]), &[2, 3]).unwrap();
+    ///
+    /// assert_eq!(a.get_slice(&[0..2, 3..0]).unwrap(), b);
+    /// ```
     pub fn get_slice(&self, indices: &[Range<usize>]) -> Result<Tensor<T>, TensorError> {
         if self.dims.len() < indices.len() {
             return Err(TensorError::DimError);
@@ -388,11 +581,22 @@ impl<T: Clone + TensorType> Tensor<T> {
         for i in 0..(self.dims.len() - indices.len()) {
             full_indices.push(0..self.dims()[indices.len() + i])
         }
-        for e in full_indices.iter().cloned().multi_cartesian_product() {
+        // descending ranges are read back to front
+        let coords: Vec<Vec<usize>> = full_indices
+            .iter()
+            .map(|e| {
+                if e.start > e.end {
+                    (e.end..e.start).rev().collect()
+                } else {
+                    e.clone().collect()
+                }
+            })
+            .collect();
+        for e in coords.iter().cloned().multi_cartesian_product() {
             let index = self.get_index(&e);
             res.push(self[index].clone())
         }
-        let mut dims: Vec<usize> = full_indices.iter().map(|e| e.end - e.start).collect();
+        let mut dims: Vec<usize> = coords.iter().map(|e| e.len()).collect();
         for i in (0..indices.len()).rev() {
             if (dims[i] == 1) && (dims.len() > 1) {
                 dims.remove(i);
@@ -402,6 +606,127 @@ impl<T: Clone + TensorType> Tensor<T> {
         Tensor::new(Some(&res), &dims)
     }
 
+    /// Borrows a region of the Tensor without copying it, for instance to take many windows of a tensor in a hot loop.
+    /// Unlike `get_slice` unit dimensions are kept.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap();
+    /// let v = a.view_slice(&[0..2, 1..3]).unwrap();
+    /// assert_eq!(v.dims(), &[2, 2]);
+    /// assert_eq!(v.iter().cloned().collect::<Vec<_>>(), vec![2, 3, 5, 6]);
+    /// assert_eq!(v.to_tensor(), a.get_slice(&[0..2, 1..3]).unwrap());
+    /// ```
+    pub fn view_slice(&self, indices: &[Range<usize>]) -> Result<TensorView<'_, T>, TensorError> {
+        if self.dims.len() < indices.len() {
+            return Err(TensorError::DimError);
+        }
+        // if indices weren't specified we fill them in as required
+        let mut ranges = indices.to_vec();
+        for i in 0..(self.dims.len() - indices.len()) {
+            ranges.push(0..self.dims()[indices.len() + i])
+        }
+        for (range, dim) in ranges.iter().zip(self.dims.iter()) {
+            if (range.start > range.end) || (range.end > *dim) {
+                return Err(TensorError::DimError);
+            }
+        }
+        Ok(TensorView {
+            parent: self,
+            dims: ranges.iter().map(|r| r.end - r.start).collect(),
+            ranges,
+        })
+    }
+
+    /// Returns the `length` elements starting at `start` along `axis`, taking every other dimension in full.
+    /// Unlike `get_slice`, the number of dimensions is always preserved.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&(0..16).collect::<Vec<_>>()), &[4, 4]).unwrap();
+    /// let b = a.narrow(0, 1, 2).unwrap();
+    /// assert_eq!(b, Tensor::<i32>::new(Some(&[4, 5, 6, 7, 8, 9, 10, 11]), &[2, 4]).unwrap());
+    /// assert!(a.narrow(1, 3, 2).is_err());
+    /// ```
+    pub fn narrow(
+        &self,
+        axis: usize,
+        start: usize,
+        length: usize,
+    ) -> Result<Tensor<T>, TensorError> {
+        if (axis >= self.dims.len()) || (start + length > self.dims[axis]) {
+            return Err(TensorError::DimError);
+        }
+        let indices: Vec<Range<usize>> = self
+            .dims
+            .iter()
+            .enumerate()
+            .map(|(i, d)| {
+                if i == axis {
+                    start..(start + length)
+                } else {
+                    0..*d
+                }
+            })
+            .collect();
+        let mut t = self.get_slice(&indices)?;
+        // get_slice drops unit dimensions so we restore them
+        let mut dims = self.dims.clone();
+        dims[axis] = length;
+        t.reshape(&dims);
+        Ok(t)
+    }
+
+    /// Writes `value` into the region of the Tensor addressed by `indices` (the inverse of `get_slice`).
+    /// Unspecified trailing dimensions are taken in full. As `get_slice` drops unit dimensions, these are ignored when
+    /// checking that the shape of the region matches `value.dims()`.
+    ///
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let mut a = Tensor::<i32>::new(Some(&[1, 2, 3]), &[3]).unwrap();
+    /// let b = Tensor::<i32>::new(Some(&[4, 5]), &[2]).unwrap();
+    ///
+    /// a.set_slice(&[1..3], &b).unwrap();
+    /// assert_eq!(a, Tensor::<i32>::new(Some(&[1, 4, 5]), &[3]).unwrap());
+    /// ```
+    pub fn set_slice(
+        &mut self,
+        indices: &[Range<usize>],
+        value: &Tensor<T>,
+    ) -> Result<(), TensorError> {
+        if self.dims.len() < indices.len() {
+            return Err(TensorError::DimError);
+        }
+        // if indices weren't specified we fill them in as required
+        let mut full_indices = indices.to_vec();
+        for i in 0..(self.dims.len() - indices.len()) {
+            full_indices.push(0..self.dims()[indices.len() + i])
+        }
+        for (range, dim) in full_indices.iter().zip(self.dims.iter()) {
+            if (range.start > range.end) || (range.end > *dim) {
+                return Err(TensorError::DimError);
+            }
+        }
+
+        let region_dims = full_indices
+            .iter()
+            .map(|e| e.end - e.start)
+            .filter(|d| *d != 1);
+        let value_dims = value.dims().iter().cloned().filter(|d| *d != 1);
+        if !region_dims.eq(value_dims) {
+            return Err(TensorError::DimMismatch("set_slice".to_string()));
+        }
+
+        for (e, v) in full_indices
+            .iter()
+            .cloned()
+            .multi_cartesian_product()
+            .zip(value.iter())
+        {
+            let index = self.get_index(&e);
+            self[index] = v.clone();
+        }
+        Ok(())
+    }
+
     /// Get the array index from rows / columns indices.
     ///
     /// ```
@@ -430,6 +755,64 @@ impl<T: Clone + TensorType> Tensor<T> {
         &self.dims
     }
 
+    /// Checks that the tensor has the same dimensions as `other`.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+    /// assert!(a.matches_shape(&Tensor::<i32>::new(None, &[2, 3]).unwrap()));
+    /// assert!(!a.matches_shape(&Tensor::<i32>::new(None, &[3, 2]).unwrap()));
+    /// ```
+    pub fn matches_shape(&self, other: &Tensor<T>) -> bool {
+        self.dims == other.dims
+    }
+
+    /// Checks that the tensor has shape `dims`, erroring with both shapes otherwise.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+    /// assert!(a.expect_dims(&[2, 3]).is_ok());
+    /// assert_eq!(
+    ///     a.expect_dims(&[6]).unwrap_err().to_string(),
+    ///     "dimension mismatch in tensor op: expected shape [6], got [2, 3]"
+    /// );
+    /// ```
+    pub fn expect_dims(&self, dims: &[usize]) -> Result<(), TensorError> {
+        if self.dims != dims {
+            return Err(TensorError::DimMismatch(format!(
+                "expected shape {:?}, got {:?}",
+                dims, self.dims
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the `(rows, cols)` of a 2D tensor, erroring for tensors of any other rank.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+    /// assert_eq!(a.as_matrix().unwrap(), (2, 3));
+    /// ```
+    pub fn as_matrix(&self) -> Result<(usize, usize), TensorError> {
+        match self.dims[..] {
+            [rows, cols] => Ok((rows, cols)),
+            _ => Err(TensorError::DimMismatch(format!(
+                "expected a matrix, got a tensor of shape {:?}",
+                self.dims
+            ))),
+        }
+    }
+
+    /// Checks that the tensor has no more than `capacity` elements, e.g. before assigning it to a [VarTensor].
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+    /// assert!(a.fits_capacity(6));
+    /// assert!(!a.fits_capacity(5));
+    /// ```
+    pub fn fits_capacity(&self, capacity: usize) -> bool {
+        self.len() <= capacity
+    }
+
     ///Reshape the tensor
     /// ```
     /// use ezkl::tensor::Tensor;
@@ -439,7 +822,46 @@ impl<T: Clone + TensorType> Tensor<T> {
     /// ```
     pub fn reshape(&mut self, new_dims: &[usize]) {
         assert!(self.len() == new_dims.iter().product::<usize>());
+        self.make_contiguous();
         self.dims = Vec::from(new_dims);
+        self.strides = contiguous_strides(new_dims);
+    }
+
+    /// Reshapes the tensor to the dimensions of `other`, which may hold a different element type. Errors if the
+    /// element counts differ.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let mut a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6]), &[6]).unwrap();
+    /// let b = Tensor::<f32>::new(None, &[2, 3]).unwrap();
+    /// a.reshape_like(&b).unwrap();
+    /// assert_eq!(a.dims(), &[2, 3]);
+    /// assert!(a.reshape_like(&Tensor::<f32>::new(None, &[4]).unwrap()).is_err());
+    /// ```
+    pub fn reshape_like<U: TensorType>(&mut self, other: &Tensor<U>) -> Result<(), TensorError> {
+        if self.len() != other.len() {
+            return Err(TensorError::DimMismatch("reshape_like".to_string()));
+        }
+        self.reshape(other.dims());
+        Ok(())
+    }
+
+    /// Prepends unit dimensions until the tensor has `rank` dimensions, such that operands of different ranks line up
+    /// for broadcasting. Errors if the tensor already has more than `rank` dimensions.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let mut a = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+    /// a.align_rank(4).unwrap();
+    /// assert_eq!(a.dims(), &[1, 1, 2, 3]);
+    /// assert!(a.align_rank(3).is_err());
+    /// ```
+    pub fn align_rank(&mut self, rank: usize) -> Result<(), TensorError> {
+        if self.dims.len() > rank {
+            return Err(TensorError::DimError);
+        }
+        let mut dims = vec![1; rank - self.dims.len()];
+        dims.extend(self.dims());
+        self.reshape(&dims);
+        Ok(())
     }
 
     ///Flatten the tensor shape
@@ -450,7 +872,214 @@ impl<T: Clone + TensorType> Tensor<T> {
     /// assert_eq!(a.dims(), &[27]);
     /// ```
     pub fn flatten(&mut self) {
+        self.make_contiguous();
         self.dims = Vec::from([self.dims.iter().product::<usize>()]);
+        self.strides = vec![1];
+    }
+
+    ///Returns a flattened copy of the tensor, leaving the original untouched
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<f32>::new(None, &[3, 3, 3]).unwrap();
+    /// let b = a.flattened();
+    /// assert_eq!(b.dims(), &[27]);
+    /// assert_eq!(a.dims(), &[3, 3, 3]);
+    /// ```
+    pub fn flattened(&self) -> Tensor<T> {
+        let mut t = self.clone();
+        t.flatten();
+        t
+    }
+
+    /// Returns the tensor's strides, i.e. the step in the underlying storage between consecutive elements along each
+    /// dimension.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(None, &[2, 3, 4]).unwrap();
+    /// assert_eq!(a.strides(), &[12, 4, 1]);
+    /// ```
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// Checks if the tensor's elements are laid out contiguously in row-major order.
+    pub fn is_contiguous(&self) -> bool {
+        self.strides == contiguous_strides(&self.dims)
+    }
+
+    /// Rearranges the underlying storage such that the tensor is contiguous, leaving its logical contents unchanged:
+    /// the elements are gathered through `strides` into a new row-major buffer.
+    pub fn make_contiguous(&mut self) {
+        if self.is_contiguous() {
+            return;
+        }
+        let inner = self
+            .dims
+            .iter()
+            .map(|d| 0..*d)
+            .multi_cartesian_product()
+            .map(|coord| {
+                let index: usize = coord
+                    .iter()
+                    .zip(self.strides.iter())
+                    .map(|(c, s)| c * s)
+                    .sum();
+                self.inner[index].clone()
+            })
+            .collect();
+        self.inner = inner;
+        self.strides = contiguous_strides(&self.dims);
+    }
+
+    /// Folds the tensor along `axis` using `f`, starting each fold from `init`. The reduced axis is removed from the
+    /// output's dimensions (reducing a 1D tensor yields a tensor of shape `[1]`).
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[1, 5, 2, 7, 0, 3]), &[2, 3]).unwrap();
+    /// let max_rows = a.reduce(1, i32::MIN, |acc, x| acc.max(x)).unwrap();
+    /// assert_eq!(max_rows, Tensor::<i32>::new(Some(&[5, 7]), &[2]).unwrap());
+    /// let max_cols = a.reduce(0, i32::MIN, |acc, x| acc.max(x)).unwrap();
+    /// let expected: Vec<i32> = (0..3).map(|j| a.get(&[0, j]).max(a.get(&[1, j]))).collect();
+    /// assert_eq!(max_cols, Tensor::<i32>::new(Some(&expected), &[3]).unwrap());
+    /// ```
+    pub fn reduce<F: Fn(T, T) -> T>(
+        &self,
+        axis: usize,
+        init: T,
+        f: F,
+    ) -> Result<Tensor<T>, TensorError> {
+        if axis >= self.dims.len() {
+            return Err(TensorError::DimError);
+        }
+        let outer: usize = self.dims[..axis].iter().product();
+        let len = self.dims[axis];
+        let inner: usize = self.dims[axis + 1..].iter().product();
+
+        let mut res = Vec::new();
+        for o in 0..outer {
+            for i in 0..inner {
+                let mut acc = init.clone();
+                for j in 0..len {
+                    acc = f(acc, self[(o * len + j) * inner + i].clone());
+                }
+                res.push(acc);
+            }
+        }
+
+        let mut dims = self.dims.clone();
+        dims.remove(axis);
+        if dims.is_empty() {
+            dims.push(1);
+        }
+        Tensor::new(Some(&res), &dims)
+    }
+
+    /// Iterates over consecutive `row_len`-sized chunks of the tensor's underlying storage, the last of which may be
+    /// shorter. With `row_len` set to a [VarTensor]'s `col_size` each chunk holds the elements `assign` places in a
+    /// single column, which is handy when debugging a layout.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6, 7]), &[7]).unwrap();
+    /// let rows: Vec<&[i32]> = a.rows(3).collect();
+    /// assert_eq!(rows, vec![&[1, 2, 3][..], &[4, 5, 6][..], &[7][..]]);
+    /// ```
+    pub fn rows(&self, row_len: usize) -> impl Iterator<Item = &[T]> {
+        assert!(row_len > 0);
+        self.inner.chunks(row_len)
+    }
+
+    /// Iterates over the sliding windows of shape `window` taken every `stride` elements along each dimension,
+    /// in row-major order. Each window is returned as a [Tensor] of shape `window`. Errors if `window` or `stride`
+    /// don't have one entry per dimension, or if a stride is zero.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6]), &[1, 6]).unwrap();
+    /// let sums: Vec<i32> = a.windows(&[1, 2], &[1, 2]).unwrap().map(|w| w.iter().sum()).collect();
+    /// assert_eq!(sums, vec![3, 7, 11]);
+    /// assert!(a.windows(&[2], &[1]).is_err());
+    /// assert!(a.windows(&[1, 2], &[1, 0]).is_err());
+    /// ```
+    pub fn windows<'a>(
+        &'a self,
+        window: &[usize],
+        stride: &[usize],
+    ) -> Result<impl Iterator<Item = Tensor<T>> + 'a, TensorError> {
+        if self.dims.len() != window.len() || self.dims.len() != stride.len() {
+            return Err(TensorError::DimMismatch("windows".to_string()));
+        }
+        if stride.contains(&0) {
+            return Err(TensorError::DimError);
+        }
+        let (window, stride) = (window.to_vec(), stride.to_vec());
+        let slides: Vec<Range<usize>> = self
+            .dims
+            .iter()
+            .zip(window.iter().zip(stride.iter()))
+            .map(|(d, (w, s))| if w > d { 0..0 } else { 0..((d - w) / s + 1) })
+            .collect();
+
+        Ok(slides
+            .into_iter()
+            .multi_cartesian_product()
+            .map(move |coord| {
+                // every window lies within the tensor, so we can gather its elements directly
+                let inner = coord
+                    .iter()
+                    .zip(window.iter().zip(stride.iter()))
+                    .map(|(c, (w, s))| (c * s)..(c * s + w))
+                    .multi_cartesian_product()
+                    .map(|idx| self.get(&idx))
+                    .collect();
+                Tensor {
+                    inner,
+                    dims: window.clone(),
+                    strides: contiguous_strides(&window),
+                }
+            }))
+    }
+
+    /// Converts a 4D tensor from `N x C x H x W` to `N x H x W x C` layout.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&(0..12).collect::<Vec<_>>()), &[1, 2, 2, 3]).unwrap();
+    /// let b = a.to_nhwc().unwrap();
+    /// assert_eq!(b.dims(), &[1, 2, 3, 2]);
+    /// assert_eq!(b.get(&[0, 1, 2, 0]), a.get(&[0, 0, 1, 2]));
+    /// assert_eq!(b.to_nchw().unwrap(), a);
+    /// ```
+    pub fn to_nhwc(&self) -> Result<Tensor<T>, TensorError> {
+        self.permute_4d([0, 2, 3, 1])
+    }
+
+    /// Converts a 4D tensor from `N x H x W x C` to `N x C x H x W` layout.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&(0..12).collect::<Vec<_>>()), &[1, 2, 2, 3]).unwrap();
+    /// let b = a.to_nchw().unwrap();
+    /// assert_eq!(b.dims(), &[1, 3, 2, 2]);
+    /// assert_eq!(b.get(&[0, 2, 1, 0]), a.get(&[0, 1, 0, 2]));
+    /// assert_eq!(b.to_nhwc().unwrap(), a);
+    /// ```
+    pub fn to_nchw(&self) -> Result<Tensor<T>, TensorError> {
+        self.permute_4d([0, 3, 1, 2])
+    }
+
+    /// Permutes the axes of a 4D tensor, such that output axis `i` is input axis `axes[i]`.
+    fn permute_4d(&self, axes: [usize; 4]) -> Result<Tensor<T>, TensorError> {
+        if self.dims.len() != 4 {
+            return Err(TensorError::DimMismatch("permute".to_string()));
+        }
+        validate_permutation(&axes, 4)?;
+        let dims: Vec<usize> = axes.iter().map(|a| self.dims[*a]).collect();
+        let mut res = Vec::new();
+        for coord in dims.iter().map(|d| 0..*d).multi_cartesian_product() {
+            let mut src = [0; 4];
+            for (i, a) in axes.iter().enumerate() {
+                src[*a] = coord[i];
+            }
+            res.push(self.get(&src));
+        }
+        Tensor::new(Some(&res), &dims)
     }
 
     /// Maps a function to tensors
@@ -488,6 +1117,22 @@ impl<T: Clone + TensorType> Tensor<T> {
         Ok(t)
     }
 
+    /// Iterates over the elements of the tensor in row-major order, alongside their multi-dimensional coordinates.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap();
+    /// for (coord, v) in a.iter_coords() {
+    ///     assert_eq!(a.get(&coord), *v);
+    /// }
+    /// ```
+    pub fn iter_coords(&self) -> impl Iterator<Item = (Vec<usize>, &T)> {
+        self.dims
+            .iter()
+            .map(|d| 0..*d)
+            .multi_cartesian_product()
+            .zip(self.iter())
+    }
+
     /// Maps a function to tensors and enumerates using multi cartesian coordinates
     /// ```
     /// use ezkl::tensor::Tensor;
@@ -499,13 +1144,9 @@ impl<T: Clone + TensorType> Tensor<T> {
         &self,
         mut f: F,
     ) -> Result<Tensor<G>, TensorError> {
-        let mut indices = Vec::new();
-        for i in self.dims.clone() {
-            indices.push(0..i);
-        }
         let mut res = Vec::new();
-        for coord in indices.iter().cloned().multi_cartesian_product() {
-            res.push(f(&coord, self.get(&coord)));
+        for (coord, v) in self.iter_coords() {
+            res.push(f(&coord, v.clone()));
         }
 
         Tensor::new(Some(&res), self.dims())
@@ -533,6 +1174,86 @@ impl<T: Clone + TensorType> Tensor<Tensor<T>> {
     }
 }
 
+impl Tensor<i32> {
+    /// Renders the float values a fixed point tensor represents, i.e. each element divided by the multiplier `scale`
+    /// (see [crate::graph::scale_to_multiplier]), for comparing against the outputs of other frameworks when debugging.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[4, -2, 1, 0]), &[2, 2]).unwrap();
+    /// assert_eq!(a.debug_dequantized(4.0), "[1.0, -0.5, 0.25, 0.0] (dims [2, 2])");
+    /// ```
+    pub fn debug_dequantized(&self, scale: f32) -> String {
+        format!("{:?} (dims {:?})", self.to_f32_vec(scale), self.dims)
+    }
+
+    /// Returns the float values a fixed point tensor represents, i.e. each element divided by the multiplier `scale`
+    /// (see [crate::graph::scale_to_multiplier]), in row-major order, e.g. to compute accuracy metrics or plot outputs.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// let a = Tensor::<i32>::new(Some(&[4, -2, 1, 0]), &[2, 2]).unwrap();
+    /// assert_eq!(a.to_f32_vec(4.0), vec![1.0, -0.5, 0.25, 0.0]);
+    /// ```
+    pub fn to_f32_vec(&self, scale: f32) -> Vec<f32> {
+        self.inner.iter().map(|e| *e as f32 / scale).collect()
+    }
+}
+
+/// A borrowed rectangular region of a [Tensor], as returned by [Tensor::view_slice].
+#[derive(Clone, Debug)]
+pub struct TensorView<'a, T: TensorType> {
+    parent: &'a Tensor<T>,
+    ranges: Vec<Range<usize>>,
+    dims: Vec<usize>,
+}
+
+impl<'a, T: TensorType> TensorView<'a, T> {
+    /// Returns the dimensions of the region.
+    pub fn dims(&self) -> &[usize] {
+        &self.dims
+    }
+
+    /// Returns the number of elements in the region.
+    pub fn len(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    /// Checks if the region holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the elements of the region in row-major order, borrowing them from the parent [Tensor].
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let parent = self.parent;
+        let ranges = self.ranges.clone();
+        let mut coord: Vec<usize> = ranges.iter().map(|r| r.start).collect();
+        let mut remaining = self.len();
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            remaining -= 1;
+            let item = &parent[parent.get_index(&coord)];
+            // advance to the next coordinate, the last axis moving fastest
+            for i in (0..coord.len()).rev() {
+                coord[i] += 1;
+                if coord[i] < ranges[i].end {
+                    break;
+                }
+                coord[i] = ranges[i].start;
+            }
+            Some(item)
+        })
+    }
+
+    /// Copies the region into a new [Tensor] of shape `dims()`.
+    pub fn to_tensor(&self) -> Tensor<T> {
+        let mut t = Tensor::from(self.iter().cloned());
+        t.reshape(self.dims());
+        t
+    }
+}
+
 ////////////////////////
 
 #[cfg(test)]
@@ -546,6 +1267,22 @@ mod tests {
         assert_eq!(&tensor[..], &data[..]);
     }
 
+    #[test]
+    fn felt_tmax_is_ordered_within_felt_to_i32_range() {
+        use halo2curves::pasta::Fp;
+
+        let max = |a: i32, b: i32| felt_to_i32(i32_to_felt::<Fp>(a).tmax(&i32_to_felt(b)).unwrap());
+        assert_eq!(max(65536, 0), 65536);
+        assert_eq!(max(-65536, 0), 0);
+        assert_eq!(max(-65536, 65536), 65536);
+        assert_eq!(max(65535, 65536), 65536);
+
+        // past the boundary the ordering no longer holds
+        let (a, b) = (i32_to_felt::<Fp>(65536), i32_to_felt::<Fp>(65537));
+        assert_ne!(felt_to_i32(b), 65537);
+        assert_eq!(a.tmax(&b), Some(a));
+    }
+
     #[test]
     fn tensor_clone() {
         let x = Tensor::<i32>::new(Some(&[1, 2, 3]), &[3]).unwrap();
@@ -570,4 +1307,265 @@ mod tests {
         let b = Tensor::<i32>::new(Some(&[1, 4]), &[2]).unwrap();
         assert_eq!(a.get_slice(&[0..2, 0..1]).unwrap(), b);
     }
+
+    #[test]
+    fn tensor_windows() {
+        let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6, 7, 8, 9]), &[1, 3, 3]).unwrap();
+        let windows: Vec<Tensor<i32>> = a.windows(&[1, 2, 2], &[1, 1, 1]).unwrap().collect();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(
+            windows[0],
+            Tensor::<i32>::new(Some(&[1, 2, 4, 5]), &[1, 2, 2]).unwrap()
+        );
+        assert_eq!(
+            windows[3],
+            Tensor::<i32>::new(Some(&[5, 6, 8, 9]), &[1, 2, 2]).unwrap()
+        );
+        // with a stride of 2 only the top left window fits
+        assert_eq!(a.windows(&[1, 2, 2], &[1, 2, 2]).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn tensor_rows_follow_var_tensor_layout() {
+        let mut cs = ConstraintSystem::<halo2curves::pasta::Fp>::default();
+        let var = VarTensor::new_advice(&mut cs, 4, 20, vec![20], true, 512);
+        let col_size = match &var {
+            VarTensor::Advice { col_size, .. } => *col_size,
+            _ => unreachable!(),
+        };
+        let a = Tensor::<usize>::new(Some(&(0..20).collect::<Vec<_>>()), &[20]).unwrap();
+        let mut count = 0;
+        for (col, row) in a.rows(col_size).enumerate() {
+            assert!(row.len() <= col_size);
+            for (offset, i) in row.iter().enumerate() {
+                assert_eq!(var.cartesian_coord(*i), (col, offset));
+                count += 1;
+            }
+        }
+        assert_eq!(count, 20);
+    }
+
+    #[test]
+    fn field_max_pool_uses_signed_ordering() {
+        use halo2curves::pasta::Fp;
+        let x = Tensor::<i32>::new(Some(&[-5, 2, -3, 0, -4, -1, 3, -7, -6]), &[1, 3, 3]).unwrap();
+        let expected = ops::max_pool2d(&x, (0, 0), (1, 1), (2, 2)).unwrap();
+        assert_eq!(expected[..], [2, 2, 3, -1]);
+
+        let xf = x.map(i32_to_felt::<Fp>);
+        let pooled = ops::max_pool2d(&xf, (0, 0), (1, 1), (2, 2)).unwrap();
+        assert_eq!(pooled, expected.map(i32_to_felt::<Fp>));
+    }
+
+    #[test]
+    fn to_f32_vec_matches_dequantize_f64() {
+        use crate::graph::utilities::{dequantize_f64, scale_to_multiplier};
+        let a = Tensor::<i32>::new(Some(&[64, -32, 3, 256, -1, 0]), &[3, 2]).unwrap();
+        let expected: Vec<f32> = dequantize_f64(&a, 0.0, 7)
+            .iter()
+            .map(|e| *e as f32)
+            .collect();
+        assert_eq!(a.to_f32_vec(scale_to_multiplier(7)), expected);
+        assert_eq!(
+            a.debug_dequantized(scale_to_multiplier(7)),
+            format!("{:?} (dims [3, 2])", expected)
+        );
+    }
+
+    #[test]
+    fn tensor_views_match_copies() {
+        let a = Tensor::<i32>::new(
+            Some(&(0..48).map(|e| (e * 7) % 11 - 5).collect::<Vec<_>>()),
+            &[3, 4, 4],
+        )
+        .unwrap();
+        let k = Tensor::<i32>::new(
+            Some(&(0..12).map(|e| e - 6).collect::<Vec<_>>()),
+            &[3, 2, 2],
+        )
+        .unwrap();
+        for (i, j) in (0..3).cartesian_product(0..3) {
+            let indices = [0..3, i..(i + 2), j..(j + 2)];
+            let view = a.view_slice(&indices).unwrap();
+            let copy = a.get_slice(&indices).unwrap();
+            assert_eq!(view.to_tensor(), copy);
+            assert_eq!(
+                ops::dot_views(&view, &k.view_slice(&[]).unwrap()).unwrap(),
+                ops::dot(&vec![&copy, &k]).unwrap()
+            );
+            // views borrow the parent's elements rather than copying them
+            let first = view.iter().next().unwrap();
+            assert!(std::ptr::eq(first, &a[a.get_index(&[0, i, j])]));
+        }
+        assert!(a.view_slice(&[0..4]).is_err());
+    }
+
+    #[test]
+    fn tensor_strides() {
+        let mut a = Tensor::<i32>::new(Some(&(0..24).collect::<Vec<_>>()), &[2, 3, 4]).unwrap();
+        assert_eq!(a.strides(), &[12, 4, 1]);
+        assert!(a.is_contiguous());
+
+        let slice = a.get_slice(&[0..2, 1..3]).unwrap();
+        assert_eq!(slice.dims(), &[2, 2, 4]);
+        assert_eq!(slice.strides(), &[8, 4, 1]);
+        assert!(slice.is_contiguous());
+
+        a.reshape(&[6, 4]);
+        assert_eq!(a.strides(), &[4, 1]);
+        a.flatten();
+        assert_eq!(a.strides(), &[1]);
+        assert!(a.is_contiguous());
+
+        let b = a.clone();
+        a.make_contiguous();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn make_contiguous_gathers_through_strides() {
+        // the transpose of [[0, 1, 2], [3, 4, 5]], laid out over the original's storage
+        let mut t = Tensor::<i32> {
+            inner: (0..6).collect(),
+            dims: vec![3, 2],
+            strides: vec![1, 3],
+        };
+        assert!(!t.is_contiguous());
+
+        t.make_contiguous();
+        assert!(t.is_contiguous());
+        assert_eq!(t.strides(), &[2, 1]);
+        assert_eq!(t, Tensor::new(Some(&[0, 3, 1, 4, 2, 5]), &[3, 2]).unwrap());
+    }
+
+    #[test]
+    fn tensor_try_from_iter() {
+        let a = Tensor::try_from_iter((0..9).map(|x| x * 2), &[3, 3]).unwrap();
+        assert_eq!(a.dims(), &[3, 3]);
+        assert_eq!(a.get(&[2, 1]), 14);
+        // too few elements
+        assert!(Tensor::try_from_iter(0..8, &[3, 3]).is_err());
+        // too many elements, without draining the (infinite) iterator
+        assert!(Tensor::try_from_iter(0.., &[3, 3]).is_err());
+    }
+
+    #[test]
+    fn tensor_set_slice() {
+        let mut a = Tensor::<i32>::new(None, &[4, 4]).unwrap();
+        let b = Tensor::<i32>::new(Some(&[1, 2, 3, 4]), &[2, 2]).unwrap();
+        a.set_slice(&[1..3, 2..4], &b).unwrap();
+        let expected = Tensor::<i32>::new(
+            Some(&[0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 3, 4, 0, 0, 0, 0]),
+            &[4, 4],
+        )
+        .unwrap();
+        assert_eq!(a, expected);
+        assert_eq!(a.get_slice(&[1..3, 2..4]).unwrap(), b);
+        // region shape doesn't match the value
+        assert!(a.set_slice(&[0..3, 2..4], &b).is_err());
+        // region out of bounds
+        assert!(a.set_slice(&[3..5, 2..4], &b).is_err());
+    }
+
+    #[test]
+    fn permutations_are_validated() {
+        assert!(validate_permutation(&[], 0).is_ok());
+        assert!(validate_permutation(&[1, 0], 2).is_ok());
+        // duplicate axes
+        assert!(validate_permutation(&[1, 1], 2).is_err());
+        assert!(validate_permutation(&[0, 1, 0], 3).is_err());
+        // out of range axes
+        assert!(validate_permutation(&[0, 2], 2).is_err());
+        // wrong length
+        assert!(validate_permutation(&[0, 1], 3).is_err());
+        assert!(validate_permutation(&[0, 1, 2], 2).is_err());
+    }
+
+    #[test]
+    fn tensor_stack_batch() {
+        let images = (0..3)
+            .map(|i| Tensor::try_from_iter((0..784).map(|x| x + i * 784), &[1, 28, 28]).unwrap())
+            .collect::<Vec<_>>();
+        let batch = Tensor::stack_batch(&images).unwrap();
+        assert_eq!(batch.dims(), &[3, 1, 28, 28]);
+        for (i, image) in images.iter().enumerate() {
+            let mut slice = batch.get_slice(&[i..i + 1]).unwrap();
+            slice.reshape(&[1, 28, 28]);
+            assert_eq!(&slice, image);
+        }
+        // images must share a shape
+        let odd = Tensor::<i32>::new(None, &[1, 28, 27]).unwrap();
+        assert!(Tensor::stack_batch(&[images[0].clone(), odd]).is_err());
+        assert!(Tensor::<i32>::stack_batch(&[]).is_err());
+    }
+
+    #[test]
+    fn tensor_descending_slice_matches_flip() {
+        let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6, 7, 8, 9]), &[1, 3, 3]).unwrap();
+        let mut reversed = a.get_slice(&[0..1, 0..3, 3..0]).unwrap();
+        // get_slice drops the unit batch dimension
+        reversed.reshape(&[1, 3, 3]);
+        assert_eq!(reversed, crate::tensor::ops::flip(&a, 2).unwrap());
+
+        let mut reversed = a.get_slice(&[0..1, 3..0]).unwrap();
+        reversed.reshape(&[1, 3, 3]);
+        assert_eq!(reversed, crate::tensor::ops::flip(&a, 1).unwrap());
+
+        // partial descending ranges read a reversed window
+        let window = a.get_slice(&[0..1, 1..2, 3..1]).unwrap();
+        assert_eq!(window, Tensor::<i32>::new(Some(&[6, 5]), &[2]).unwrap());
+    }
+
+    #[test]
+    fn align_rank_prepends_unit_dims() {
+        let mut a = Tensor::<i32>::new(Some(&[1, 2, 3]), &[3]).unwrap();
+        a.align_rank(3).unwrap();
+        assert_eq!(a.dims(), &[1, 1, 3]);
+        assert_eq!(a.get(&[0, 0, 2]), 3);
+        // already at the requested rank
+        a.align_rank(3).unwrap();
+        assert_eq!(a.dims(), &[1, 1, 3]);
+        assert!(a.align_rank(2).is_err());
+        assert_eq!(a.dims(), &[1, 1, 3]);
+    }
+
+    #[test]
+    fn as_matrix_rejects_non_2d() {
+        let vector = Tensor::<i32>::new(None, &[4]).unwrap();
+        assert!(matches!(
+            vector.as_matrix(),
+            Err(TensorError::DimMismatch(_))
+        ));
+        let cube = Tensor::<i32>::new(None, &[2, 2, 2]).unwrap();
+        assert!(matches!(cube.as_matrix(), Err(TensorError::DimMismatch(_))));
+
+        // affine rejects a stray 3D kernel rather than misreading its dims
+        let input = Tensor::<i32>::new(Some(&[1, 2]), &[2]).unwrap();
+        let bias = Tensor::<i32>::new(Some(&[0, 0]), &[2]).unwrap();
+        let kernel = Tensor::<i32>::new(Some(&[1; 8]), &[2, 2, 2]).unwrap();
+        assert!(ops::affine(&vec![input.clone(), kernel, bias.clone()]).is_err());
+        let kernel = Tensor::<i32>::new(Some(&[1, 0, 0, 1]), &[2, 2]).unwrap();
+        assert_eq!(
+            ops::affine(&vec![input.clone(), kernel, bias]).unwrap(),
+            input
+        );
+    }
+
+    #[test]
+    fn iter_coords_is_row_major() {
+        let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4]), &[2, 2]).unwrap();
+        let walked = a
+            .iter_coords()
+            .map(|(coord, v)| (coord, *v))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            walked,
+            vec![
+                (vec![0, 0], 1),
+                (vec![0, 1], 2),
+                (vec![1, 0], 3),
+                (vec![1, 1], 4)
+            ]
+        );
+    }
 }
@@ -1,7 +1,104 @@
 use super::TensorError;
-use crate::tensor::{Tensor, TensorType};
+use crate::tensor::{Tensor, TensorType, TensorView};
 use itertools::Itertools;
-pub use std::ops::{Add, Div, Mul, Sub};
+pub use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Checks that all `tensors` have the same dimensions, as required by elementwise ops.
+/// # Arguments
+///
+/// * `tensors` - The tensors to check.
+/// * `op` - Name of the operation, used in the error.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::check_same_dims;
+///
+/// let x = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+/// let y = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+/// let z = Tensor::<i32>::new(None, &[3, 2]).unwrap();
+/// assert!(check_same_dims(&[&x, &y], "add").is_ok());
+/// assert!(check_same_dims(&[&x, &y, &z], "add").is_err());
+/// // same number of elements isn't enough
+/// assert!(check_same_dims(&[&x, &Tensor::<i32>::new(None, &[6]).unwrap()], "add").is_err());
+/// ```
+pub fn check_same_dims<T: TensorType>(tensors: &[&Tensor<T>], op: &str) -> Result<(), TensorError> {
+    for t in tensors.iter() {
+        if !tensors[0].matches_shape(t) {
+            return Err(TensorError::DimMismatch(op.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `a` and `b` can be matrix multiplied: both have at least 2 dimensions, the same leading (batch)
+/// dimensions, and the last dimension of `a` matches the second to last dimension of `b`. The error names which of
+/// these failed, along with the offending dimensions.
+/// # Arguments
+///
+/// * `a` - Tensor.
+/// * `b` - Tensor.
+/// * `op` - Name of the operation, used in the error.
+/// # Examples
+/// ```
+/// use ezkl::tensor::{Tensor, TensorError};
+/// use ezkl::tensor::ops::check_matmul_dims;
+///
+/// let a = Tensor::<i32>::new(None, &[2, 3]).unwrap();
+/// assert!(check_matmul_dims(&a, &Tensor::<i32>::new(None, &[3, 4]).unwrap(), "matmul").is_ok());
+/// // inner dimensions don't match
+/// let err = check_matmul_dims(&a, &Tensor::<i32>::new(None, &[2, 4]).unwrap(), "matmul");
+/// match err {
+///     Err(TensorError::DimMismatch(msg)) => {
+///         assert_eq!(msg, "matmul: inner dims mismatch, lhs [2, 3] has 3 columns but rhs [2, 4] has 2 rows")
+///     }
+///     _ => panic!(),
+/// }
+/// // batch dimensions don't match
+/// let x = Tensor::<i32>::new(None, &[2, 2, 3]).unwrap();
+/// let err = check_matmul_dims(&x, &Tensor::<i32>::new(None, &[3, 3, 4]).unwrap(), "matmul");
+/// match err {
+///     Err(TensorError::DimMismatch(msg)) => {
+///         assert_eq!(msg, "matmul: batch dims mismatch, lhs [2] vs rhs [3]")
+///     }
+///     _ => panic!(),
+/// }
+/// // ranks don't match, or are too small
+/// assert!(check_matmul_dims(&x, &Tensor::<i32>::new(None, &[3, 4]).unwrap(), "matmul").is_err());
+/// assert!(check_matmul_dims(&Tensor::<i32>::new(None, &[3]).unwrap(), &a, "matmul").is_err());
+/// ```
+pub fn check_matmul_dims<T: TensorType>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    op: &str,
+) -> Result<(), TensorError> {
+    let (a_dims, b_dims) = (a.dims(), b.dims());
+    let rank = a_dims.len();
+    if (rank < 2) || (b_dims.len() != rank) {
+        return Err(TensorError::DimMismatch(format!(
+            "{}: rank mismatch, lhs {:?} vs rhs {:?} (both need at least 2 dims)",
+            op, a_dims, b_dims
+        )));
+    }
+    if a_dims[rank - 1] != b_dims[rank - 2] {
+        return Err(TensorError::DimMismatch(format!(
+            "{}: inner dims mismatch, lhs {:?} has {} columns but rhs {:?} has {} rows",
+            op,
+            a_dims,
+            a_dims[rank - 1],
+            b_dims,
+            b_dims[rank - 2]
+        )));
+    }
+    if a_dims[0..rank - 2] != b_dims[0..rank - 2] {
+        return Err(TensorError::DimMismatch(format!(
+            "{}: batch dims mismatch, lhs {:?} vs rhs {:?}",
+            op,
+            &a_dims[0..rank - 2],
+            &b_dims[0..rank - 2]
+        )));
+    }
+    Ok(())
+}
 
 /// Matrix multiplies two 2D tensors (and adds an offset).
 /// # Arguments
@@ -31,32 +128,33 @@ pub use std::ops::{Add, Div, Mul, Sub};
 pub fn affine<T: TensorType + Mul<Output = T> + Add<Output = T>>(
     inputs: &Vec<Tensor<T>>,
 ) -> Result<Tensor<T>, TensorError> {
-    let (mut input, kernel, bias) = (inputs[0].clone(), inputs[1].clone(), inputs[2].clone());
-    if (inputs.len() != 3)
-        || (bias.dims()[0] != kernel.dims()[0])
-        || (input.dims()[0] != kernel.dims()[1])
-    {
+    if inputs.len() != 3 {
         return Err(TensorError::DimMismatch("affine".to_string()));
     }
+    let (mut input, kernel, bias) = (inputs[0].clone(), inputs[1].clone(), inputs[2].clone());
 
     // does matrix to vector multiplication
     if input.dims().len() == 1 {
         input.reshape(&[input.dims()[0], 1])
     }
 
-    let input_dims = input.dims();
-    let kernel_dims = kernel.dims();
+    let (out_dim, in_dim) = kernel.as_matrix()?;
+    let (input_rows, input_cols) = input.as_matrix()?;
+    bias.expect_dims(&[out_dim])?;
+    if input_rows != in_dim {
+        return Err(TensorError::DimMismatch("affine".to_string()));
+    }
 
     // calculate value of output
-    let mut output: Tensor<T> = Tensor::new(None, &[kernel_dims[0], input_dims[1]]).unwrap();
+    let mut output: Tensor<T> = Tensor::new(None, &[out_dim, input_cols]).unwrap();
 
-    for i in 0..kernel_dims[0] {
-        for j in 0..input_dims[1] {
+    for i in 0..out_dim {
+        for j in 0..input_cols {
             let prod = dot(&vec![
                 &kernel.get_slice(&[i..i + 1])?,
-                &input.get_slice(&[0..input_dims[0], j..j + 1])?,
+                &input.get_slice(&[0..input_rows, j..j + 1])?,
             ])?;
-            output.set(&[i, j], prod[0].clone() + bias[i].clone());
+            output.set(&[i, j], prod.scalar()?.clone() + bias[i].clone());
         }
     }
     // does matrix to vector multiplication
@@ -95,12 +193,10 @@ pub fn affine<T: TensorType + Mul<Output = T> + Add<Output = T>>(
 pub fn scale_and_shift<T: TensorType + Mul<Output = T> + Add<Output = T>>(
     inputs: &Vec<Tensor<T>>,
 ) -> Result<Tensor<T>, TensorError> {
-    if (inputs.len() != 3)
-        || (inputs[1].dims() != inputs[2].dims())
-        || (inputs[0].dims() != inputs[1].dims())
-    {
+    if inputs.len() != 3 {
         return Err(TensorError::DimMismatch("scale and shift".to_string()));
     }
+    check_same_dims(&[&inputs[0], &inputs[1], &inputs[2]], "scale and shift")?;
     let (input, kernel, bias) = (inputs[0].clone(), inputs[1].clone(), inputs[2].clone());
     let mut output: Tensor<T> = input;
     for (i, bias_i) in bias.iter().enumerate() {
@@ -133,13 +229,11 @@ pub fn scale_and_shift<T: TensorType + Mul<Output = T> + Add<Output = T>>(
 pub fn matmul<T: TensorType + Mul<Output = T> + Add<Output = T>>(
     inputs: &Vec<Tensor<T>>,
 ) -> Result<Tensor<T>, TensorError> {
-    let (a, b) = (inputs[0].clone(), inputs[1].clone());
-    if (inputs.len() != 2)
-        || (a.dims()[a.dims().len() - 1] != b.dims()[a.dims().len() - 2])
-        || (a.dims()[0..a.dims().len() - 2] != b.dims()[0..a.dims().len() - 2])
-    {
+    if inputs.len() != 2 {
         return Err(TensorError::DimMismatch("matmul".to_string()));
     }
+    let (a, b) = (inputs[0].clone(), inputs[1].clone());
+    check_matmul_dims(&a, &b, "matmul")?;
 
     let mut dims = Vec::from(&a.dims()[0..a.dims().len() - 2]);
     dims.push(a.dims()[a.dims().len() - 2]);
@@ -166,6 +260,132 @@ pub fn matmul<T: TensorType + Mul<Output = T> + Add<Output = T>>(
     Ok(output)
 }
 
+/// Contracts tensors according to an einsum `equation`, e.g. `"ij,jk->ik"` for a matrix multiplication.
+/// Each index is a single lowercase letter. Indices which appear in the inputs but not in the output are summed over.
+/// The output (`->...`) must be given explicitly.
+/// # Arguments
+///
+/// * `equation` - the einsum equation, with one comma-separated term per operand.
+/// * `operands` - the tensors to contract.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::{einsum, matmul};
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6, 2, 1, 1]),
+///     &[3, 4],
+/// ).unwrap();
+/// let k = Tensor::<i32>::new(
+///     Some(&[2, 1, 2, 1, 1, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let result = einsum("ij,jk->ik", &[&k, &x]).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[26, 7, 11, 3, 15, 3, 7, 2]), &[2, 4]).unwrap();
+/// assert_eq!(result, expected);
+///
+/// // batched matmul
+/// let a = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 1, 0, 0, 1]), &[2, 2, 2]).unwrap();
+/// let b = Tensor::<i32>::new(Some(&[1, 1, 0, 1, 2, 3, 4, 5]), &[2, 2, 2]).unwrap();
+/// let result = einsum("bij,bjk->bik", &[&a, &b]).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[1, 3, 3, 7, 2, 3, 4, 5]), &[2, 2, 2]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn einsum<T: TensorType + Mul<Output = T> + Add<Output = T>>(
+    equation: &str,
+    operands: &[&Tensor<T>],
+) -> Result<Tensor<T>, TensorError> {
+    let err = || TensorError::DimMismatch("einsum".to_string());
+    let (inputs, output) = equation.split_once("->").ok_or_else(err)?;
+    let inputs: Vec<Vec<char>> = inputs
+        .split(',')
+        .map(|t| t.trim().chars().collect())
+        .collect();
+    let output: Vec<char> = output.trim().chars().collect();
+    if inputs.len() != operands.len() || operands.is_empty() {
+        return Err(err());
+    }
+
+    // the size of each index, checked for consistency across operands
+    let mut sizes: Vec<(char, usize)> = vec![];
+    for (term, operand) in inputs.iter().zip(operands.iter()) {
+        if term.len() != operand.dims().len() {
+            return Err(err());
+        }
+        for (c, d) in term.iter().zip(operand.dims().iter()) {
+            if !c.is_ascii_lowercase() {
+                return Err(err());
+            }
+            match sizes.iter().find(|(s, _)| s == c) {
+                Some((_, size)) if size != d => return Err(err()),
+                Some(_) => {}
+                None => sizes.push((*c, *d)),
+            }
+        }
+    }
+    let size_of = |c: &char| sizes.iter().find(|(s, _)| s == c).map(|(_, d)| *d);
+
+    let mut dims = vec![];
+    for (i, c) in output.iter().enumerate() {
+        if output[..i].contains(c) {
+            return Err(err());
+        }
+        dims.push(size_of(c).ok_or_else(err)?);
+    }
+    let summed: Vec<char> = sizes
+        .iter()
+        .map(|(c, _)| *c)
+        .filter(|c| !output.contains(c))
+        .collect();
+
+    // position of each operand's indices within [output..., summed...]
+    let all: Vec<char> = output.iter().chain(summed.iter()).cloned().collect();
+    let positions: Vec<Vec<usize>> = inputs
+        .iter()
+        .map(|term| {
+            term.iter()
+                .map(|c| all.iter().position(|a| a == c).unwrap())
+                .collect()
+        })
+        .collect();
+
+    // every coordinate over `ranges` in row-major order, including the single empty coordinate when there are no ranges
+    let coords = |ranges: Vec<usize>| -> Vec<Vec<usize>> {
+        if ranges.is_empty() {
+            return vec![vec![]];
+        }
+        ranges
+            .iter()
+            .map(|d| 0..*d)
+            .multi_cartesian_product()
+            .collect()
+    };
+    let summed_coords = coords(summed.iter().map(|c| size_of(c).unwrap()).collect());
+
+    // a scalar output is represented as a tensor of shape [1]
+    let mut output_tensor: Tensor<T> =
+        Tensor::new(None, if dims.is_empty() { &[1][..] } else { &dims[..] }).unwrap();
+
+    for (i, out_coord) in coords(dims).into_iter().enumerate() {
+        let mut acc = T::zero().ok_or(TensorError::WrongMethod)?;
+        for sum_coord in summed_coords.iter() {
+            let coord: Vec<usize> = out_coord.iter().chain(sum_coord.iter()).cloned().collect();
+            let prod = operands
+                .iter()
+                .zip(positions.iter())
+                .map(|(operand, pos)| {
+                    operand.get(&pos.iter().map(|p| coord[*p]).collect::<Vec<_>>())
+                })
+                .reduce(|a, b| a * b)
+                .unwrap();
+            acc = acc + prod;
+        }
+        output_tensor[i] = acc;
+    }
+
+    Ok(output_tensor)
+}
+
 /// Adds multiple tensors.
 /// # Arguments
 ///
@@ -191,11 +411,7 @@ pub fn add<T: TensorType + Add<Output = T>>(t: &Vec<Tensor<T>>) -> Result<Tensor
     if t.len() == 2 && t[1].dims().len() == 1 && t[1].dims()[0] == 1 {
         return const_add(&t[0], t[1][0].clone());
     }
-    for e in t.iter() {
-        if t[0].dims() != e.dims() {
-            return Err(TensorError::DimMismatch("add".to_string()));
-        }
-    }
+    check_same_dims(&t.iter().collect::<Vec<_>>(), "add")?;
     // calculate value of output
     let mut output: Tensor<T> = t[0].clone();
 
@@ -240,6 +456,45 @@ pub fn const_add<T: TensorType + Add<Output = T>>(
     Ok(output)
 }
 
+/// Adds a per-channel bias to a tensor of shape C x H x W, such that `bias[c]` is added to every spatial position of channel `c`.
+/// # Arguments
+///
+/// * `a` - Tensor whose leading dimension indexes channels
+/// * `bias` - 1D tensor of length C
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::add_channel_bias;
+/// let x = Tensor::<i32>::new(
+///     Some(&[1, 2, 3, 4, 5, 6, 7, 8]),
+///     &[2, 2, 2],
+/// ).unwrap();
+/// let b = Tensor::<i32>::new(
+///     Some(&[10, 20]),
+///     &[2],
+/// ).unwrap();
+/// let result = add_channel_bias(&x, &b).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[11, 12, 13, 14, 25, 26, 27, 28]), &[2, 2, 2]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn add_channel_bias<T: TensorType + Add<Output = T>>(
+    a: &Tensor<T>,
+    bias: &Tensor<T>,
+) -> Result<Tensor<T>, TensorError> {
+    if a.dims().is_empty() || (bias.dims().len() != 1) || (bias.dims()[0] != a.dims()[0]) {
+        return Err(TensorError::DimMismatch("add_channel_bias".to_string()));
+    }
+    let channel_size = a.dims()[1..].iter().product::<usize>();
+    // calculate value of output
+    let mut output: Tensor<T> = a.clone();
+
+    for i in 0..output.len() {
+        output[i] = output[i].clone() + bias[i / channel_size].clone();
+    }
+
+    Ok(output)
+}
+
 /// Subtracts multiple tensors.
 /// # Arguments
 ///
@@ -267,11 +522,7 @@ pub fn sub<T: TensorType + Sub<Output = T>>(t: &Vec<Tensor<T>>) -> Result<Tensor
         return const_sub(&t[0], t[1][0].clone());
     }
 
-    for e in t.iter() {
-        if t[0].dims() != e.dims() {
-            return Err(TensorError::DimMismatch("sub".to_string()));
-        }
-    }
+    check_same_dims(&t.iter().collect::<Vec<_>>(), "sub")?;
     // calculate value of output
     let mut output: Tensor<T> = t[0].clone();
 
@@ -316,6 +567,168 @@ pub fn const_sub<T: TensorType + Sub<Output = T>>(
     Ok(output)
 }
 
+/// Elementwise negates a tensor.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::negate;
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, -1, 0, 1, -3, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let result = negate(&x);
+/// let expected = Tensor::<i32>::new(Some(&[-2, 1, 0, -1, 3, -1]), &[2, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// assert_eq!(-x, expected);
+/// ```
+pub fn negate<T: TensorType + Neg<Output = T>>(a: &Tensor<T>) -> Tensor<T> {
+    // calculate value of output
+    let mut output: Tensor<T> = a.clone();
+
+    for i in 0..output.len() {
+        output[i] = -output[i].clone();
+    }
+
+    output
+}
+
+/// Splits a tensor of integers into its positive part, `max(x, 0)`, and the magnitude of its negative part,
+/// `max(-x, 0)`, such that subtracting the latter from the former reconstructs the tensor. Both parts are
+/// non-negative, as needed by sign-decomposition gadgets.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::{split_sign, sub};
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, -1, 0, 1, -3, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let (pos, neg) = split_sign(&x);
+/// assert_eq!(pos, Tensor::<i32>::new(Some(&[2, 0, 0, 1, 0, 1]), &[2, 3]).unwrap());
+/// assert_eq!(neg, Tensor::<i32>::new(Some(&[0, 1, 0, 0, 3, 0]), &[2, 3]).unwrap());
+/// assert_eq!(sub(&vec![pos, neg]).unwrap(), x);
+/// ```
+pub fn split_sign(a: &Tensor<i32>) -> (Tensor<i32>, Tensor<i32>) {
+    (a.map(|x| x.max(0)), a.map(|x| (-x).max(0)))
+}
+
+/// Zeroes the elements of a tensor where a mask of the same shape is zero, for instance to reproduce a fixed
+/// dropout pattern (dropout is otherwise the identity at inference time).
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `mask` - Tensor of the same shape, where zero elements drop the corresponding element of `a`.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::apply_mask;
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, 1, 2, 1, 1, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let mask = Tensor::<i32>::new(
+///     Some(&[1, 0, 1, 0, 0, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let result = apply_mask(&x, &mask).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[2, 0, 2, 0, 0, 1]), &[2, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn apply_mask<T: TensorType>(
+    a: &Tensor<T>,
+    mask: &Tensor<i32>,
+) -> Result<Tensor<T>, TensorError> {
+    if a.dims() != mask.dims() {
+        return Err(TensorError::DimMismatch("apply_mask".to_string()));
+    }
+    let zero = T::zero().ok_or(TensorError::WrongMethod)?;
+    // calculate value of output
+    let mut output: Tensor<T> = a.clone();
+
+    for i in 0..output.len() {
+        if mask[i] == 0 {
+            output[i] = zero.clone();
+        }
+    }
+
+    Ok(output)
+}
+
+/// Zeroes out the elements above the `diagonal`-th diagonal of the last two dimensions of a tensor.
+/// `diagonal = 0` keeps the main diagonal, positive values keep diagonals above it and negative values drop diagonals below it.
+/// # Arguments
+///
+/// * `a` - Tensor with at least 2 dimensions
+/// * `diagonal` - the diagonal relative to which elements are kept
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::tril;
+/// let x = Tensor::<i32>::new(
+///     Some(&[1, 1, 1, 1, 1, 1, 1, 1, 1]),
+///     &[3, 3],
+/// ).unwrap();
+/// let result = tril(&x, 0).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[1, 0, 0, 1, 1, 0, 1, 1, 1]), &[3, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn tril<T: TensorType>(a: &Tensor<T>, diagonal: i32) -> Result<Tensor<T>, TensorError> {
+    triangular(a, |row, col| col - row <= diagonal as i64, "tril")
+}
+
+/// Zeroes out the elements below the `diagonal`-th diagonal of the last two dimensions of a tensor.
+/// `diagonal = 0` keeps the main diagonal, positive values drop diagonals above it and negative values keep diagonals below it.
+/// # Arguments
+///
+/// * `a` - Tensor with at least 2 dimensions
+/// * `diagonal` - the diagonal relative to which elements are kept
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::triu;
+/// let x = Tensor::<i32>::new(
+///     Some(&[1, 1, 1, 1, 1, 1, 1, 1, 1]),
+///     &[3, 3],
+/// ).unwrap();
+/// let result = triu(&x, 1).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[0, 1, 1, 0, 0, 1, 0, 0, 0]), &[3, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn triu<T: TensorType>(a: &Tensor<T>, diagonal: i32) -> Result<Tensor<T>, TensorError> {
+    triangular(a, |row, col| col - row >= diagonal as i64, "triu")
+}
+
+/// Zeroes out every element of the last two dimensions whose (row, col) position fails `keep`.
+fn triangular<T: TensorType>(
+    a: &Tensor<T>,
+    keep: impl Fn(i64, i64) -> bool,
+    name: &str,
+) -> Result<Tensor<T>, TensorError> {
+    let n = a.dims().len();
+    if n < 2 {
+        return Err(TensorError::DimMismatch(name.to_string()));
+    }
+    let (rows, cols) = (a.dims()[n - 2], a.dims()[n - 1]);
+    let zero = T::zero().ok_or(TensorError::WrongMethod)?;
+    // calculate value of output
+    let mut output: Tensor<T> = a.clone();
+
+    for i in 0..output.len() {
+        let (row, col) = ((i / cols) % rows, i % cols);
+        if !keep(row as i64, col as i64) {
+            output[i] = zero.clone();
+        }
+    }
+
+    Ok(output)
+}
+
 /// Elementwise multiplies two tensors.
 /// # Arguments
 ///
@@ -343,11 +756,7 @@ pub fn mult<T: TensorType + Mul<Output = T>>(t: &Vec<Tensor<T>>) -> Result<Tenso
         return const_mult(&t[0], t[1][0].clone());
     }
 
-    for e in t.iter() {
-        if t[0].dims() != e.dims() {
-            return Err(TensorError::DimMismatch("mult".to_string()));
-        }
-    }
+    check_same_dims(&t.iter().collect::<Vec<_>>(), "mult")?;
     // calculate value of output
     let mut output: Tensor<T> = t[0].clone();
 
@@ -385,9 +794,7 @@ pub fn div<T: TensorType + Div<Output = T>>(
     t: Tensor<T>,
     d: Tensor<T>,
 ) -> Result<Tensor<T>, TensorError> {
-    if t.dims() != d.dims() {
-        return Err(TensorError::DimMismatch("div".to_string()));
-    }
+    check_same_dims(&[&t, &d], "div")?;
     // calculate value of output
     let mut output: Tensor<T> = t;
 
@@ -429,6 +836,45 @@ pub fn const_mult<T: TensorType + Mul<Output = T>>(
     Ok(output)
 }
 
+/// Multiplies a tensor of shape C x H x W by a per-channel scale, such that every spatial position of channel `c` is multiplied by `scale[c]`.
+/// # Arguments
+///
+/// * `a` - Tensor whose leading dimension indexes channels
+/// * `scale` - 1D tensor of length C
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::mul_channel_scale;
+/// let x = Tensor::<i32>::new(
+///     Some(&[1, 2, 3, 4, 5, 6, 7, 8]),
+///     &[2, 2, 2],
+/// ).unwrap();
+/// let s = Tensor::<i32>::new(
+///     Some(&[2, -1]),
+///     &[2],
+/// ).unwrap();
+/// let result = mul_channel_scale(&x, &s).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[2, 4, 6, 8, -5, -6, -7, -8]), &[2, 2, 2]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn mul_channel_scale<T: TensorType + Mul<Output = T>>(
+    a: &Tensor<T>,
+    scale: &Tensor<T>,
+) -> Result<Tensor<T>, TensorError> {
+    if a.dims().is_empty() || (scale.dims().len() != 1) || (scale.dims()[0] != a.dims()[0]) {
+        return Err(TensorError::DimMismatch("mul_channel_scale".to_string()));
+    }
+    let channel_size = a.dims()[1..].iter().product::<usize>();
+    // calculate value of output
+    let mut output: Tensor<T> = a.clone();
+
+    for i in 0..output.len() {
+        output[i] = output[i].clone() * scale[i / channel_size].clone();
+    }
+
+    Ok(output)
+}
+
 /// Rescale a tensor with a const integer (similar to const_mult).
 /// # Arguments
 ///
@@ -492,6 +938,48 @@ pub fn pow<T: TensorType + Mul<Output = T>>(
     Ok(output)
 }
 
+/// Elementwise raises each element of a tensor to its own power, given by the matching element of `exponents`, e.g.
+/// to build polynomial features. Exponents must be at least 1, as there is no generic multiplicative identity to
+/// return for `x^0`: a zero exponent errors with [TensorError::ZeroExponent] and the index of the first one.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `exponents` - Tensor of the same shape as `a`
+/// # Examples
+/// ```
+/// use ezkl::tensor::{Tensor, TensorError};
+/// use ezkl::tensor::ops::pow_elementwise;
+/// let x = Tensor::<i32>::new(Some(&[2, 15, -2, 1, 3, 0]), &[2, 3]).unwrap();
+/// let e = Tensor::<usize>::new(Some(&[1, 2, 3, 4, 3, 2]), &[2, 3]).unwrap();
+/// let result = pow_elementwise(&x, &e).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[2, 225, -8, 1, 27, 0]), &[2, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// // shapes must match
+/// assert!(pow_elementwise(&x, &Tensor::<usize>::new(Some(&[1; 6]), &[6]).unwrap()).is_err());
+/// // and exponents must be at least 1
+/// let zero = Tensor::<usize>::new(Some(&[1, 2, 0, 4, 0, 2]), &[2, 3]).unwrap();
+/// assert!(matches!(pow_elementwise(&x, &zero), Err(TensorError::ZeroExponent(2))));
+/// ```
+pub fn pow_elementwise<T: TensorType + Mul<Output = T>>(
+    a: &Tensor<T>,
+    exponents: &Tensor<usize>,
+) -> Result<Tensor<T>, TensorError> {
+    if a.dims() != exponents.dims() {
+        return Err(TensorError::DimMismatch("pow_elementwise".to_string()));
+    }
+    if let Some(i) = exponents.iter().position(|e| *e == 0) {
+        return Err(TensorError::ZeroExponent(i));
+    }
+    // calculate value of output
+    let mut output: Tensor<T> = a.clone();
+    for (i, (a_i, e)) in a.iter().zip(exponents.iter()).enumerate() {
+        for _ in 1..*e {
+            output[i] = output[i].clone() * a_i.clone();
+        }
+    }
+    Ok(output)
+}
+
 /// Sums a tensor.
 /// # Arguments
 ///
@@ -516,23 +1004,399 @@ pub fn sum<T: TensorType + Add<Output = T>>(a: &Tensor<T>) -> Result<Tensor<T>,
     Tensor::new(Some(&[res]), &[1])
 }
 
-/// Applies convolution over a 3D tensor of shape C x H x W (and adds a bias).
+/// Sums a tensor, accumulating in the wider type `W` such that the total may exceed the range of `T`.
 /// # Arguments
 ///
-/// * `inputs` - A vector of tensors holding in order: input image, convolution kernel, convolution bias.
-/// * `padding` - Tuple of padding values in x and y directions.
-/// * `stride` - Tuple of stride values in x and y directions.
+/// * `a` - Tensor
 /// # Examples
 /// ```
 /// use ezkl::tensor::Tensor;
-/// use ezkl::tensor::ops::convolution;
+/// use ezkl::tensor::ops::sum_wide;
+/// let x = Tensor::<i32>::new(Some(&[i32::MAX, i32::MAX, 2]), &[3]).unwrap();
+/// assert_eq!(sum_wide::<i32, i64>(&x), 2 * (i32::MAX as i64) + 2);
+/// ```
+pub fn sum_wide<T: TensorType, W: TensorType + From<T> + Add<Output = W>>(a: &Tensor<T>) -> W {
+    a.iter()
+        .fold(W::zero().unwrap(), |acc, a_i| acc + W::from(a_i.clone()))
+}
+
+/// Sums a tensor of i32s into an i64, such that (unlike [sum]) the total can't overflow.
+/// # Arguments
 ///
-/// let x = Tensor::<i32>::new(
-///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6]),
-///     &[1, 3, 3],
-/// ).unwrap();
-/// let k = Tensor::<i32>::new(
-///     Some(&[5, 1, 1, 1]),
+/// * `a` - Tensor
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::sum_i64;
+/// // 1000 copies of 2^30 sum to 2^30 * 1000, which overflows an i32
+/// let x = Tensor::<i32>::new(Some(&vec![1 << 30; 1000]), &[10, 100]).unwrap();
+/// assert_eq!(sum_i64(&x), (1_i64 << 30) * 1000);
+/// let x = Tensor::<i32>::new(Some(&[-3, 1, -2]), &[3]).unwrap();
+/// assert_eq!(sum_i64(&x), -4);
+/// ```
+pub fn sum_i64(a: &Tensor<i32>) -> i64 {
+    sum_wide::<i32, i64>(a)
+}
+
+/// Checks that two integer tensors have the same shape and that their elements differ by at most `tol`.
+/// Useful for comparing quantized outputs, where rounding can introduce off-by-one differences.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `b` - Tensor
+/// * `tol` - Maximum allowed absolute difference between elements.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::approx_eq;
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, 1, 2, 1, 1, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let y = Tensor::<i32>::new(
+///     Some(&[3, 1, 1, 1, 2, 0]),
+///     &[2, 3],
+/// ).unwrap();
+/// assert!(approx_eq(&x, &y, 1));
+/// assert!(!approx_eq(&x, &y, 0));
+/// let z = Tensor::<i32>::new(
+///     Some(&[4, 1, 2, 1, 1, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// assert!(!approx_eq(&x, &z, 1));
+/// // differences are computed without overflowing
+/// let min = Tensor::<i32>::new(Some(&[i32::MIN]), &[1]).unwrap();
+/// let max = Tensor::<i32>::new(Some(&[i32::MAX]), &[1]).unwrap();
+/// assert!(!approx_eq(&min, &max, i32::MAX));
+/// ```
+pub fn approx_eq(a: &Tensor<i32>, b: &Tensor<i32>, tol: i32) -> bool {
+    // a negative tolerance can't be met by any (unsigned) difference
+    let tol = match u32::try_from(tol) {
+        Ok(tol) => tol,
+        Err(_) => return false,
+    };
+    a.dims() == b.dims() && a.iter().zip(b.iter()).all(|(x, y)| x.abs_diff(*y) <= tol)
+}
+
+/// Counts the elements of a tensor of integers falling in each of `bins` equal width bins spanning `min..=max`, e.g. to
+/// inspect the distribution of activations when choosing the range of a lookup table. Elements outside of the range
+/// aren't counted.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `bins` - Number of bins
+/// * `min` - Smallest value of the first bin
+/// * `max` - Largest value of the last bin
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::histogram;
+/// let x = Tensor::<i32>::new(Some(&[0, 1, 1, 3, 4, 4, 4, 7, 9, -1]), &[2, 5]).unwrap();
+/// // bins of width 2: [0, 1], [2, 3], [4, 5], [6, 7]
+/// assert_eq!(histogram(&x, 4, 0, 7), vec![3, 1, 3, 1]);
+/// assert_eq!(histogram(&x, 1, -1, 9), vec![10]);
+/// ```
+pub fn histogram(a: &Tensor<i32>, bins: usize, min: i32, max: i32) -> Vec<usize> {
+    let mut counts = vec![0; bins];
+    if bins == 0 || max < min {
+        return counts;
+    }
+    // widen such that the range of an i32 doesn't overflow
+    let range = max as i64 - min as i64 + 1;
+    for e in a.iter().filter(|e| (min..=max).contains(*e)) {
+        let bin = ((*e as i64 - min as i64) * bins as i64 / range) as usize;
+        counts[bin] += 1;
+    }
+    counts
+}
+
+/// Checks that every element of a tensor of floats is finite, erroring with the index of the first NaN or infinite
+/// element otherwise, e.g. before quantizing weights from a bad export.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::assert_finite;
+/// let x = Tensor::<f32>::new(Some(&[2.0, -1.5, 0.0]), &[3]).unwrap();
+/// assert!(assert_finite(&x).is_ok());
+/// let y = Tensor::<f32>::new(Some(&[2.0, f32::NAN, f32::INFINITY]), &[3]).unwrap();
+/// assert!(assert_finite(&y).is_err());
+/// let z = Tensor::<f64>::new(Some(&[2.0, f64::NEG_INFINITY]), &[2]).unwrap();
+/// assert!(assert_finite(&z).is_err());
+/// ```
+pub fn assert_finite<T: TensorType + Copy + Into<f64>>(a: &Tensor<T>) -> Result<(), TensorError> {
+    match a.iter().position(|e| !(*e).into().is_finite()) {
+        Some(i) => Err(TensorError::NotFinite(i)),
+        None => Ok(()),
+    }
+}
+
+/// Elementwise computes the absolute difference `|a - b|` between two tensors of integers, e.g. as the witness for a
+/// range check against an expected value.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `b` - Tensor
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::abs_diff;
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, 1, 2, -1, 1, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let y = Tensor::<i32>::new(
+///     Some(&[3, 1, 1, 1, -2, 0]),
+///     &[2, 3],
+/// ).unwrap();
+/// let result = abs_diff(&x, &y).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[1, 0, 1, 2, 3, 1]), &[2, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// assert!(abs_diff(&x, &Tensor::<i32>::new(None, &[3, 2]).unwrap()).is_err());
+/// // differences which don't fit an i32 error rather than overflowing
+/// let min = Tensor::<i32>::new(Some(&[i32::MIN]), &[1]).unwrap();
+/// let max = Tensor::<i32>::new(Some(&[i32::MAX]), &[1]).unwrap();
+/// assert!(abs_diff(&min, &max).is_err());
+/// ```
+pub fn abs_diff(a: &Tensor<i32>, b: &Tensor<i32>) -> Result<Tensor<i32>, TensorError> {
+    check_same_dims(&[a, b], "abs_diff")?;
+    // calculate value of output
+    let mut output: Tensor<i32> = a.clone();
+
+    for (i, b_i) in b.iter().enumerate() {
+        output[i] = i32::try_from(output[i].abs_diff(*b_i))
+            .map_err(|_| TensorError::Overflow("abs_diff".to_string()))?;
+    }
+
+    Ok(output)
+}
+
+/// Elementwise compares a tensor of integers against a const element, returning a mask holding 1 where `a > b` and 0
+/// elsewhere.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `b` - Single value
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::const_greater;
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, -1, 0, 1, -3, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let result = const_greater(&x, 0);
+/// let expected = Tensor::<i32>::new(Some(&[1, 0, 0, 1, 0, 1]), &[2, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn const_greater(a: &Tensor<i32>, b: i32) -> Tensor<i32> {
+    a.map(|x| (x > b) as i32)
+}
+
+/// Elementwise compares a tensor of integers against a const element, returning a mask holding 1 where `a < b` and 0
+/// elsewhere.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `b` - Single value
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::const_less;
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, -1, 0, 1, -3, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// let result = const_less(&x, 1);
+/// let expected = Tensor::<i32>::new(Some(&[0, 1, 1, 0, 1, 0]), &[2, 3]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn const_less(a: &Tensor<i32>, b: i32) -> Tensor<i32> {
+    a.map(|x| (x < b) as i32)
+}
+
+/// Returns true if every element of a tensor is nonzero. An empty tensor returns true.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::all;
+/// let x = Tensor::<i32>::new(
+///     Some(&[2, 1, 2, 1, 1, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// assert!(all(&x));
+/// let y = Tensor::<i32>::new(
+///     Some(&[2, 1, 0, 1, 1, 1]),
+///     &[2, 3],
+/// ).unwrap();
+/// assert!(!all(&y));
+/// ```
+pub fn all(a: &Tensor<i32>) -> bool {
+    a.iter().all(|x| *x != 0)
+}
+
+/// Returns true if at least one element of a tensor is nonzero. An empty tensor returns false.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::any;
+/// let x = Tensor::<i32>::new(
+///     Some(&[0, 0, 3, 0, 0, 0]),
+///     &[2, 3],
+/// ).unwrap();
+/// assert!(any(&x));
+/// let y = Tensor::<i32>::new(
+///     Some(&[0, 0, 0, 0, 0, 0]),
+///     &[2, 3],
+/// ).unwrap();
+/// assert!(!any(&y));
+/// ```
+pub fn any(a: &Tensor<i32>) -> bool {
+    a.iter().any(|x| *x != 0)
+}
+
+/// Returns the indices that would sort each slice of a tensor along `axis`.
+/// The sort is stable: elements that compare equal (or are unordered, e.g. NaNs) keep their original relative order,
+/// in both ascending and descending mode.
+/// # Arguments
+///
+/// * `a` - Tensor
+/// * `axis` - The axis along which to sort.
+/// * `descending` - Whether to sort from largest to smallest.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::argsort;
+/// let x = Tensor::<i32>::new(
+///     Some(&[3, 1, 2, 1]),
+///     &[4],
+/// ).unwrap();
+/// let result = argsort(&x, 0, false).unwrap();
+/// let expected = Tensor::<usize>::new(Some(&[1, 3, 2, 0]), &[4]).unwrap();
+/// assert_eq!(result, expected);
+/// let result = argsort(&x, 0, true).unwrap();
+/// let expected = Tensor::<usize>::new(Some(&[0, 2, 1, 3]), &[4]).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn argsort<T: TensorType + PartialOrd>(
+    a: &Tensor<T>,
+    axis: usize,
+    descending: bool,
+) -> Result<Tensor<usize>, TensorError> {
+    if axis >= a.dims().len() {
+        return Err(TensorError::DimMismatch("argsort".to_string()));
+    }
+    let outer: usize = a.dims()[..axis].iter().product();
+    let len = a.dims()[axis];
+    let inner: usize = a.dims()[axis + 1..].iter().product();
+
+    let mut output = Tensor::<usize>::new(None, a.dims())?;
+    for o in 0..outer {
+        for i in 0..inner {
+            let index = |j: usize| (o * len + j) * inner + i;
+            let mut perm: Vec<usize> = (0..len).collect();
+            // sort_by is stable
+            perm.sort_by(|x, y| {
+                let (x, y) = (&a[index(*x)], &a[index(*y)]);
+                let ord = if descending {
+                    y.partial_cmp(x)
+                } else {
+                    x.partial_cmp(y)
+                };
+                ord.unwrap_or(std::cmp::Ordering::Equal)
+            });
+            for (j, p) in perm.into_iter().enumerate() {
+                output[index(j)] = p;
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Returns the height and width of the output of a [convolution] (or pooling) over an image, using the same formula.
+/// # Arguments
+///
+/// * `image` - Dimensions of the image, the last two of which are its height and width (e.g. `[C, H, W]`).
+/// * `kernel` - Dimensions of the kernel, the last two of which are its height and width (e.g. `[O, C, KH, KW]`).
+/// * `padding` - Tuple of padding values in x and y directions.
+/// * `stride` - Tuple of stride values in x and y directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::ops::conv_output_dims;
+/// assert_eq!(conv_output_dims(&[1, 28, 28], &[4, 1, 5, 5], (0, 0), (1, 1)), (24, 24));
+/// assert_eq!(conv_output_dims(&[1, 28, 28], &[4, 1, 5, 5], (2, 2), (2, 2)), (14, 14));
+/// assert_eq!(conv_output_dims(&[3, 7, 9], &[2, 3, 3, 2], (1, 0), (2, 3)), (4, 3));
+/// ```
+pub fn conv_output_dims(
+    image: &[usize],
+    kernel: &[usize],
+    padding: (usize, usize),
+    stride: (usize, usize),
+) -> (usize, usize) {
+    let (image_height, image_width) = (image[image.len() - 2], image[image.len() - 1]);
+    let (kernel_height, kernel_width) = (kernel[kernel.len() - 2], kernel[kernel.len() - 1]);
+    (
+        (image_height + 2 * padding.0 - kernel_height) / stride.0 + 1,
+        (image_width + 2 * padding.1 - kernel_width) / stride.1 + 1,
+    )
+}
+
+/// Returns the smallest (symmetric) padding for which a [convolution] produces an output of at least
+/// `ceil(H / stride.0) x ceil(W / stride.1)`, i.e. "same" padding. The output is exactly that size whenever the total
+/// padding needed in a direction is even, as is the case for odd kernels with a stride of 1.
+/// # Arguments
+///
+/// * `image` - Dimensions of the image, the last two of which are its height and width (e.g. `[C, H, W]`).
+/// * `kernel` - Dimensions of the kernel, the last two of which are its height and width (e.g. `[O, C, KH, KW]`).
+/// * `stride` - Tuple of stride values in x and y directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::ops::{conv_output_dims, same_padding};
+/// let padding = same_padding(&[1, 28, 28], &[4, 1, 5, 5], (1, 1));
+/// assert_eq!(padding, (2, 2));
+/// assert_eq!(conv_output_dims(&[1, 28, 28], &[4, 1, 5, 5], padding, (1, 1)), (28, 28));
+///
+/// let padding = same_padding(&[1, 28, 28], &[4, 1, 3, 3], (2, 2));
+/// assert_eq!(padding, (1, 1));
+/// assert_eq!(conv_output_dims(&[1, 28, 28], &[4, 1, 3, 3], padding, (2, 2)), (14, 14));
+/// ```
+pub fn same_padding(image: &[usize], kernel: &[usize], stride: (usize, usize)) -> (usize, usize) {
+    let pad = |size: usize, kernel_size: usize, stride: usize| {
+        let target = (size + stride - 1) / stride;
+        let total = ((target - 1) * stride + kernel_size).saturating_sub(size);
+        (total + 1) / 2
+    };
+    (
+        pad(image[image.len() - 2], kernel[kernel.len() - 2], stride.0),
+        pad(image[image.len() - 1], kernel[kernel.len() - 1], stride.1),
+    )
+}
+
+/// Applies convolution over a 3D tensor of shape C x H x W (and adds a bias).
+/// # Arguments
+///
+/// * `inputs` - A vector of tensors holding in order: input image, convolution kernel, convolution bias.
+/// * `padding` - Tuple of padding values in x and y directions.
+/// * `stride` - Tuple of stride values in x and y directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::convolution;
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6]),
+///     &[1, 3, 3],
+/// ).unwrap();
+/// let k = Tensor::<i32>::new(
+///     Some(&[5, 1, 1, 1]),
 ///     &[1, 1, 2, 2],
 /// ).unwrap();
 /// let b = Tensor::<i32>::new(
@@ -575,12 +1439,9 @@ pub fn convolution<T: TensorType + Mul<Output = T> + Add<Output = T>>(
         kernel_dims[3],
     );
 
-    let (image_height, image_width) = (image_dims[1], image_dims[2]);
-
     let padded_image = pad::<T>(&image, padding)?;
 
-    let vert_slides = (image_height + 2 * padding.0 - kernel_height) / stride.0 + 1;
-    let horz_slides = (image_width + 2 * padding.1 - kernel_width) / stride.1 + 1;
+    let (vert_slides, horz_slides) = conv_output_dims(image_dims, kernel_dims, padding, stride);
 
     // calculate value of output
     let mut output: Tensor<T> =
@@ -591,27 +1452,209 @@ pub fn convolution<T: TensorType + Mul<Output = T> + Add<Output = T>>(
             let rs = j * stride.0;
             for k in 0..horz_slides {
                 let cs = k * stride.1;
-                let mut res = dot(&vec![
-                    &kernel.get_slice(&[i..i + 1])?.clone(),
-                    &padded_image.get_slice(&[
+                let mut res = dot_views(
+                    &kernel.view_slice(&[i..i + 1])?,
+                    &padded_image.view_slice(&[
                         0..input_channels,
                         rs..(rs + kernel_height),
                         cs..(cs + kernel_width),
                     ])?,
-                ])?;
+                )?
+                .scalar()?
+                .clone();
 
                 if has_bias {
                     // increment result by the bias
-                    res[0] = res[0].clone() + inputs[2][i].clone();
+                    res = res + inputs[2][i].clone();
                 }
 
-                output.set(&[i, j, k], res[0].clone());
+                output.set(&[i, j, k], res);
             }
         }
     }
     Ok(output)
 }
 
+/// Unfolds (im2col) a 3D tensor of shape `C x H x W` into a matrix of shape
+/// `[C * kernel_height * kernel_width, vert_slides * horz_slides]`, where each column holds the (padded) input patch a
+/// convolution reads for a single output position, ordered by channel, then kernel row, then kernel column. Kernel
+/// taps are `dilation` pixels apart, such that a dilated convolution is the product of its flattened kernel
+/// (of shape `[output_channels, C * kernel_height * kernel_width]`) with the unfolded input.
+/// # Arguments
+///
+/// * `image` - Tensor.
+/// * `kernel_shape` - Tuple of the kernel's height and width.
+/// * `padding` - Tuple of padding values in x and y directions.
+/// * `stride` - Tuple of stride values in x and y directions.
+/// * `dilation` - Tuple of dilation values in x and y directions, `(1, 1)` for a regular convolution.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::{convolution, im2col, matmul};
+///
+/// let x = Tensor::<i32>::new(Some(&(0..25).collect::<Vec<_>>()), &[1, 5, 5]).unwrap();
+/// let k = Tensor::<i32>::new(Some(&[5, 1, -1, 2]), &[1, 1, 2, 2]).unwrap();
+///
+/// // a kernel dilated by 2 is equivalent to a 3x3 kernel with zeros between the taps
+/// let dilated_k = Tensor::<i32>::new(Some(&[5, 0, 1, 0, 0, 0, -1, 0, 2]), &[1, 1, 3, 3]).unwrap();
+/// let expected = convolution(&vec![x.clone(), dilated_k], (1, 1), (2, 1)).unwrap();
+///
+/// let cols = im2col(&x, (2, 2), (1, 1), (2, 1), (2, 2)).unwrap();
+/// assert_eq!(cols.dims(), &[4, 15]);
+/// let mut kernel_matrix = k.clone();
+/// kernel_matrix.reshape(&[1, 4]);
+/// let mut result = matmul(&vec![kernel_matrix, cols]).unwrap();
+/// result.reshape(expected.dims());
+/// assert_eq!(result, expected);
+/// ```
+pub fn im2col<T: TensorType>(
+    image: &Tensor<T>,
+    kernel_shape: (usize, usize),
+    padding: (usize, usize),
+    stride: (usize, usize),
+    dilation: (usize, usize),
+) -> Result<Tensor<T>, TensorError> {
+    if (image.dims().len() != 3) || (dilation.0 == 0) || (dilation.1 == 0) {
+        return Err(TensorError::DimMismatch("im2col".to_string()));
+    }
+    let (kernel_height, kernel_width) = kernel_shape;
+    let input_channels = image.dims()[0];
+    // the extent of the image each (dilated) kernel application covers
+    let dilated_kernel = [
+        dilation.0 * (kernel_height - 1) + 1,
+        dilation.1 * (kernel_width - 1) + 1,
+    ];
+    let (vert_slides, horz_slides) =
+        conv_output_dims(image.dims(), &dilated_kernel, padding, stride);
+    let patch_len = input_channels * kernel_height * kernel_width;
+
+    let padded_image = pad::<T>(image, padding)?;
+
+    let mut cols: Tensor<T> = Tensor::new(None, &[patch_len, vert_slides * horz_slides]).unwrap();
+    for j in 0..vert_slides {
+        let rs = j * stride.0;
+        for k in 0..horz_slides {
+            let cs = k * stride.1;
+            let taps = (0..input_channels)
+                .cartesian_product(0..kernel_height)
+                .cartesian_product(0..kernel_width);
+            for (p, ((c, r), s)) in taps.enumerate() {
+                let value = padded_image.get(&[c, rs + r * dilation.0, cs + s * dilation.1]);
+                cols.set(&[p, j * horz_slides + k], value);
+            }
+        }
+    }
+    Ok(cols)
+}
+
+/// Applies [convolution] and additionally returns the intermediate matrices it is equivalent to, for debugging against
+/// reference implementations. Returns in order:
+/// * the convolution output of shape `[output_channels, vert_slides, horz_slides]`,
+/// * the unfolded (im2col) padded input of shape `[input_channels * kernel_height * kernel_width, vert_slides * horz_slides]`,
+///   where each column holds the input patch used for a single output position,
+/// * the flattened kernel of shape `[output_channels, input_channels * kernel_height * kernel_width]`.
+///
+/// Multiplying the flattened kernel by the unfolded input, reshaping and adding the bias reproduces the output.
+/// # Arguments
+///
+/// * `inputs` - A vector of tensors holding in order: input image, convolution kernel, convolution bias.
+/// * `padding` - Tuple of padding values in x and y directions.
+/// * `stride` - Tuple of stride values in x and y directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::{convolution_debug, matmul};
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6]),
+///     &[1, 3, 3],
+/// ).unwrap();
+/// let k = Tensor::<i32>::new(
+///     Some(&[5, 1, 1, 1]),
+///     &[1, 1, 2, 2],
+/// ).unwrap();
+/// let (result, cols, kernel) = convolution_debug::<i32>(&vec![x, k], (0, 0), (1, 1)).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[31, 16, 8, 26]), &[1, 2, 2]).unwrap();
+/// assert_eq!(result, expected);
+/// assert_eq!(cols.dims(), &[4, 4]);
+/// assert_eq!(kernel.dims(), &[1, 4]);
+///
+/// let mut reconstructed = matmul(&vec![kernel, cols]).unwrap();
+/// reconstructed.reshape(&[1, 2, 2]);
+/// assert_eq!(reconstructed, expected);
+/// ```
+pub fn convolution_debug<T: TensorType + Mul<Output = T> + Add<Output = T>>(
+    inputs: &Vec<Tensor<T>>,
+    padding: (usize, usize),
+    stride: (usize, usize),
+) -> Result<(Tensor<T>, Tensor<T>, Tensor<T>), TensorError> {
+    // also validates the dimensions of the inputs
+    let output = convolution(inputs, padding, stride)?;
+    let (image, kernel) = (inputs[0].clone(), inputs[1].clone());
+
+    let (output_channels, input_channels, kernel_height, kernel_width) = (
+        kernel.dims()[0],
+        kernel.dims()[1],
+        kernel.dims()[2],
+        kernel.dims()[3],
+    );
+    let patch_len = input_channels * kernel_height * kernel_width;
+
+    let cols = im2col(
+        &image,
+        (kernel_height, kernel_width),
+        padding,
+        stride,
+        (1, 1),
+    )?;
+
+    let mut kernel_matrix = kernel;
+    kernel_matrix.reshape(&[output_channels, patch_len]);
+
+    Ok((output, cols, kernel_matrix))
+}
+
+/// Applies convolution over a 3D tensor of integers of shape C x H x W (and adds a bias), accumulating in i64 such that
+/// intermediate sums can exceed the range of i32. Errors if an output doesn't fit in an i32.
+/// # Arguments
+///
+/// * `inputs` - A vector of tensors holding in order: input image, convolution kernel, convolution bias.
+/// * `padding` - Tuple of padding values in x and y directions.
+/// * `stride` - Tuple of stride values in x and y directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::convolution_i64;
+///
+/// // the running sum reaches 4e9, overflowing i32, before coming back down to 2e9.
+/// let x = Tensor::<i32>::new(
+///     Some(&[2_000_000_000, 2_000_000_000, -2_000_000_000]),
+///     &[1, 1, 3],
+/// ).unwrap();
+/// let k = Tensor::<i32>::new(
+///     Some(&[1, 1, 1]),
+///     &[1, 1, 1, 3],
+/// ).unwrap();
+/// let result = convolution_i64(&vec![x.clone(), k.clone()], (0, 0), (1, 1)).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[2_000_000_000]), &[1, 1, 1]).unwrap();
+/// assert_eq!(result, expected);
+///
+/// // outputs that don't fit in an i32 are an error
+/// let k = Tensor::<i32>::new(Some(&[1, 1, 0]), &[1, 1, 1, 3]).unwrap();
+/// assert!(convolution_i64(&vec![x, k], (0, 0), (1, 1)).is_err());
+/// ```
+pub fn convolution_i64(
+    inputs: &Vec<Tensor<i32>>,
+    padding: (usize, usize),
+    stride: (usize, usize),
+) -> Result<Tensor<i32>, TensorError> {
+    let wide: Vec<Tensor<i64>> = inputs.iter().map(|t| t.map(i64::from)).collect();
+    let output = convolution(&wide, padding, stride)?;
+    output.enum_map(|_, x| {
+        i32::try_from(x).map_err(|_| TensorError::Overflow("convolution_i64".to_string()))
+    })
+}
+
 /// Applies 2D sum pooling over a 3D tensor of shape C x H x W.
 /// # Arguments
 ///
@@ -678,6 +1721,7 @@ pub fn sumpool<T: TensorType + Mul<Output = T> + Add<Output = T>>(
 }
 
 /// Applies 2D max pooling over a 3D tensor of shape C x H x W.
+/// Field elements are compared by their signed integer interpretation (see [crate::fieldutils::felt_to_i32]).
 /// # Arguments
 ///
 /// * `image` - Tensor.
@@ -781,52 +1825,496 @@ pub fn dot<T: TensorType + Mul<Output = T> + Add<Output = T>>(
     Tensor::new(Some(&[res]), &[1])
 }
 
-/// Pads a 3D tensor of shape `C x H x W` to a tensor of shape `C x (H + 2xPADDING) x (W + 2xPADDING)` using 0 values.
+/// Applies 2D max pooling over a 3D tensor of shape C x H x W, also returning for each window the flat index into
+/// `image` of its maximum, for use in unpooling. Ties are broken by picking the first maximum in row-major order.
+/// Unlike [max_pool2d], padding elements are never selected (as in ONNX / PyTorch), such that every index points at
+/// an element of `image`; errors if a window only covers padding.
+/// # Arguments
+///
+/// * `image` - Tensor.
+/// * `padding` - Tuple of padding values in x and y directions.
+/// * `stride` - Tuple of stride values in x and y directions.
+/// * `pool_dims` - Tuple of pooling window size in x and y directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::max_pool2d_with_indices;
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6]),
+///     &[1, 3, 3],
+/// ).unwrap();
+/// let (pooled, indices) = max_pool2d_with_indices::<i32>(&x, (0, 0), (1, 1), (2, 2)).unwrap();
+/// let expected: Tensor<i32> = Tensor::<i32>::new(Some(&[5, 4, 4, 6]), &[1, 2, 2]).unwrap();
+/// assert_eq!(pooled, expected);
+/// assert_eq!(indices, Tensor::<usize>::new(Some(&[0, 4, 4, 8]), &[1, 2, 2]).unwrap());
+/// for (p, i) in pooled.iter().zip(indices.iter()) {
+///     assert_eq!(*p, x[*i]);
+/// }
+/// ```
+pub fn max_pool2d_with_indices<T: TensorType + PartialOrd>(
+    image: &Tensor<T>,
+    padding: (usize, usize),
+    stride: (usize, usize),
+    pool_dims: (usize, usize),
+) -> Result<(Tensor<T>, Tensor<usize>), TensorError> {
+    if image.dims().len() != 3 {
+        return Err(TensorError::DimMismatch(
+            "max_pool2d_with_indices".to_string(),
+        ));
+    }
+    let (input_channels, image_height, image_width) =
+        (image.dims()[0], image.dims()[1], image.dims()[2]);
+
+    let (vert_slides, horz_slides) =
+        conv_output_dims(image.dims(), &[pool_dims.0, pool_dims.1], padding, stride);
+
+    let mut output: Tensor<T> =
+        Tensor::new(None, &[input_channels, vert_slides, horz_slides]).unwrap();
+    let mut indices: Tensor<usize> =
+        Tensor::new(None, &[input_channels, vert_slides, horz_slides]).unwrap();
+
+    for i in 0..input_channels {
+        for j in 0..vert_slides {
+            for k in 0..horz_slides {
+                let mut best: Option<usize> = None;
+                // coordinates within the padded image, skipping those that fall in the padding
+                for r in (j * stride.0)..(j * stride.0 + pool_dims.0) {
+                    for c in (k * stride.1)..(k * stride.1 + pool_dims.1) {
+                        if (r < padding.0)
+                            || (c < padding.1)
+                            || (r - padding.0 >= image_height)
+                            || (c - padding.1 >= image_width)
+                        {
+                            continue;
+                        }
+                        let index = image.get_index(&[i, r - padding.0, c - padding.1]);
+                        match best {
+                            Some(b) if image[index] <= image[b] => {}
+                            _ => best = Some(index),
+                        }
+                    }
+                }
+                let best = best.ok_or_else(|| {
+                    TensorError::DimMismatch("max_pool2d_with_indices".to_string())
+                })?;
+                output.set(&[i, j, k], image[best].clone());
+                indices.set(&[i, j, k], best);
+            }
+        }
+    }
+    Ok((output, indices))
+}
+
+/// Dot product of two regions of tensors (see [Tensor::view_slice]), which avoids copying them as [dot] on slices would.
+/// # Arguments
+///
+/// * `a` - TensorView
+/// * `b` - TensorView
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::dot_views;
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6]),
+///     &[1, 3, 3],
+/// ).unwrap();
+/// let y = Tensor::<i32>::new(
+///     Some(&[5, 5, 10, -4]),
+///     &[1, 2, 2],
+/// ).unwrap();
+/// let window = x.view_slice(&[0..1, 1..3, 1..3]).unwrap();
+/// assert_eq!(dot_views(&window, &y.view_slice(&[]).unwrap()).unwrap()[0], 1);
+/// ```
+pub fn dot_views<T: TensorType + Mul<Output = T> + Add<Output = T>>(
+    a: &TensorView<T>,
+    b: &TensorView<T>,
+) -> Result<Tensor<T>, TensorError> {
+    if a.len() != b.len() {
+        return Err(TensorError::DimMismatch("dot".to_string()));
+    }
+    let res = a
+        .iter()
+        .zip(b.iter())
+        .fold(T::zero().unwrap(), |acc, (k, i)| {
+            acc + k.clone() * i.clone()
+        });
+    Tensor::new(Some(&[res]), &[1])
+}
+
+/// Pads a 3D tensor of shape `C x H x W` to a tensor of shape `C x (H + 2xPADDING) x (W + 2xPADDING)` using 0 values.
+/// # Arguments
+///
+/// * `image` - Tensor.
+/// * `padding` - Tuple of padding values in x and y directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::pad;
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6]),
+///     &[1, 3, 3],
+/// ).unwrap();
+/// let result = pad::<i32>(&x, (1, 1)).unwrap();
+/// let expected = Tensor::<i32>::new(
+///     Some(&[0, 0, 0, 0, 0, 0, 5, 2, 3, 0, 0, 0, 4, -1, 0, 0, 3, 1, 6, 0, 0, 0, 0, 0, 0]),
+///     &[1, 5, 5],
+/// ).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn pad<T: TensorType>(
+    image: &Tensor<T>,
+    padding: (usize, usize),
+) -> Result<Tensor<T>, TensorError> {
+    if image.dims().len() != 3 {
+        return Err(TensorError::DimMismatch("pad".to_string()));
+    }
+    pad_asymmetric(image, (padding.0, padding.0, padding.1, padding.1))
+}
+
+/// Pads a 3D tensor of shape `C x H x W` to a tensor of shape `C x (H + top + bottom) x (W + left + right)` using 0 values.
+/// # Arguments
+///
+/// * `image` - Tensor.
+/// * `pads` - Tuple of padding values, in order: top, bottom, left, right.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::pad_asymmetric;
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0]),
+///     &[1, 2, 2],
+/// ).unwrap();
+/// let result = pad_asymmetric::<i32>(&x, (0, 1, 0, 1)).unwrap();
+/// let expected = Tensor::<i32>::new(
+///     Some(&[5, 2, 0, 3, 0, 0, 0, 0, 0]),
+///     &[1, 3, 3],
+/// ).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn pad_asymmetric<T: TensorType>(
+    image: &Tensor<T>,
+    pads: (usize, usize, usize, usize),
+) -> Result<Tensor<T>, TensorError> {
+    if image.dims().len() != 3 {
+        return Err(TensorError::DimMismatch("pad_asymmetric".to_string()));
+    }
+    let (top, bottom, left, right) = pads;
+    let (channels, height, width) = (image.dims()[0], image.dims()[1], image.dims()[2]);
+    let padded_height = height + top + bottom;
+    let padded_width = width + left + right;
+
+    let mut output = Tensor::<T>::new(None, &[channels, padded_height, padded_width]).unwrap();
+
+    output.set_slice(
+        &[0..channels, top..(top + height), left..(left + width)],
+        image,
+    )?;
+
+    Ok(output)
+}
+
+/// Pads the channel dimension of a 3D tensor of shape `C x H x W` to a tensor of shape `(before + C + after) x H x W` using 0 values.
+/// # Arguments
+///
+/// * `a` - Tensor.
+/// * `before` - Number of zero channels to prepend.
+/// * `after` - Number of zero channels to append.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::pad_channels;
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[5, 2, 3, 0]),
+///     &[1, 2, 2],
+/// ).unwrap();
+/// let result = pad_channels::<i32>(&x, 1, 1).unwrap();
+/// let expected = Tensor::<i32>::new(
+///     Some(&[0, 0, 0, 0, 5, 2, 3, 0, 0, 0, 0, 0]),
+///     &[3, 2, 2],
+/// ).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn pad_channels<T: TensorType>(
+    a: &Tensor<T>,
+    before: usize,
+    after: usize,
+) -> Result<Tensor<T>, TensorError> {
+    if a.dims().len() != 3 {
+        return Err(TensorError::DimMismatch("pad_channels".to_string()));
+    }
+    let (channels, height, width) = (a.dims()[0], a.dims()[1], a.dims()[2]);
+
+    let mut output = Tensor::<T>::new(None, &[before + channels + after, height, width]).unwrap();
+
+    output.set_slice(&[before..(before + channels), 0..height, 0..width], a)?;
+
+    Ok(output)
+}
+
+/// Circularly shifts the elements of a tensor along `axis` by `shift` positions, such that elements shifted past the
+/// end wrap around to the start. Negative shifts move elements towards the start instead.
+/// # Arguments
+///
+/// * `a` - Tensor.
+/// * `shift` - Number of positions to shift by.
+/// * `axis` - The axis along which to shift.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::roll;
+///
+/// let x = Tensor::<i32>::new(Some(&[1, 2, 3]), &[3]).unwrap();
+/// assert_eq!(roll(&x, 1, 0).unwrap(), Tensor::<i32>::new(Some(&[3, 1, 2]), &[3]).unwrap());
+/// assert_eq!(roll(&x, -1, 0).unwrap(), Tensor::<i32>::new(Some(&[2, 3, 1]), &[3]).unwrap());
+///
+/// let y = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[4, 5, 6, 1, 2, 3]), &[2, 3]).unwrap();
+/// assert_eq!(roll(&y, 3, 0).unwrap(), expected);
+/// ```
+pub fn roll<T: TensorType>(
+    a: &Tensor<T>,
+    shift: i32,
+    axis: usize,
+) -> Result<Tensor<T>, TensorError> {
+    if axis >= a.dims().len() {
+        return Err(TensorError::DimMismatch("roll".to_string()));
+    }
+    let len = a.dims()[axis];
+    if len == 0 {
+        return Ok(a.clone());
+    }
+    // number of contiguous elements per index of `axis`
+    let inner: usize = a.dims()[axis + 1..].iter().product();
+    let shift = (shift as i64).rem_euclid(len as i64) as usize;
+
+    let mut output: Tensor<T> = a.clone();
+    for i in 0..output.len() {
+        let (outer, j, k) = (i / (len * inner), (i / inner) % len, i % inner);
+        output[(outer * len + (j + shift) % len) * inner + k] = a[i].clone();
+    }
+    Ok(output)
+}
+
+/// Reverses the order of the elements of a tensor along `axis`.
+/// # Arguments
+///
+/// * `a` - Tensor.
+/// * `axis` - The axis to reverse.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::flip;
+///
+/// let x = Tensor::<i32>::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap();
+/// assert_eq!(flip(&x, 0).unwrap(), Tensor::<i32>::new(Some(&[4, 5, 6, 1, 2, 3]), &[2, 3]).unwrap());
+/// assert_eq!(flip(&x, 1).unwrap(), Tensor::<i32>::new(Some(&[3, 2, 1, 6, 5, 4]), &[2, 3]).unwrap());
+/// ```
+pub fn flip<T: TensorType>(a: &Tensor<T>, axis: usize) -> Result<Tensor<T>, TensorError> {
+    if axis >= a.dims().len() {
+        return Err(TensorError::DimMismatch("flip".to_string()));
+    }
+    let len = a.dims()[axis];
+    // number of contiguous elements per index of `axis`
+    let inner: usize = a.dims()[axis + 1..].iter().product();
+
+    let mut output: Tensor<T> = a.clone();
+    for i in 0..output.len() {
+        let (outer, j, k) = (i / (len * inner), (i / inner) % len, i % inner);
+        output[(outer * len + (len - 1 - j)) * inner + k] = a[i].clone();
+    }
+    Ok(output)
+}
+
+/// Computes the (rounded) mean of each sliding window of `window` consecutive elements along `axis` of a tensor of
+/// integers, e.g. to smooth a time series. Only windows fully inside the tensor are used, such that `axis` shrinks to
+/// `dims[axis] - window + 1`.
+/// # Arguments
+///
+/// * `a` - Tensor.
+/// * `window` - The number of elements averaged over.
+/// * `axis` - The axis to slide the window along.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::moving_average;
+///
+/// let x = Tensor::<i32>::new(Some(&[2, 4, 6, 9, -3]), &[5]).unwrap();
+/// let result = moving_average(&x, 2, 0).unwrap();
+/// assert_eq!(result, Tensor::<i32>::new(Some(&[3, 5, 8, 3]), &[4]).unwrap());
+/// let result = moving_average(&x, 3, 0).unwrap();
+/// assert_eq!(result, Tensor::<i32>::new(Some(&[4, 6, 4]), &[3]).unwrap());
+/// // the window can't be larger than the axis
+/// assert!(moving_average(&x, 6, 0).is_err());
+/// ```
+pub fn moving_average(
+    a: &Tensor<i32>,
+    window: usize,
+    axis: usize,
+) -> Result<Tensor<i32>, TensorError> {
+    if axis >= a.dims().len() || window == 0 || window > a.dims()[axis] {
+        return Err(TensorError::DimMismatch("moving_average".to_string()));
+    }
+    let len = a.dims()[axis];
+    let out_len = len - window + 1;
+    // number of contiguous elements per index of `axis`
+    let inner: usize = a.dims()[axis + 1..].iter().product();
+
+    let mut dims = a.dims().to_vec();
+    dims[axis] = out_len;
+    let mut output: Tensor<i32> = Tensor::new(None, &dims)?;
+    for i in 0..output.len() {
+        let (outer, j, k) = (i / (out_len * inner), (i / inner) % out_len, i % inner);
+        let sum: i64 = (j..j + window)
+            .map(|w| a[(outer * len + w) * inner + k] as i64)
+            .sum();
+        output[i] = (sum as f64 / window as f64).round() as i32;
+    }
+    Ok(output)
+}
+
+/// Upsamples a 3D tensor of shape `C x H x W` to a tensor of shape `C x (H x scale.0) x (W x scale.1)` by repeating each pixel (nearest-neighbor).
+/// # Arguments
+///
+/// * `image` - Tensor.
+/// * `scale` - Tuple of integer upsampling factors in the y and x directions.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::resize_nearest;
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[1, 2, 3, 4]),
+///     &[1, 2, 2],
+/// ).unwrap();
+/// let result = resize_nearest::<i32>(&x, (2, 2)).unwrap();
+/// let expected = Tensor::<i32>::new(
+///     Some(&[1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]),
+///     &[1, 4, 4],
+/// ).unwrap();
+/// assert_eq!(result, expected);
+/// ```
+pub fn resize_nearest<T: TensorType>(
+    image: &Tensor<T>,
+    scale: (usize, usize),
+) -> Result<Tensor<T>, TensorError> {
+    if image.dims().len() != 3 {
+        return Err(TensorError::DimMismatch("resize_nearest".to_string()));
+    }
+    let (channels, height, width) = (image.dims()[0], image.dims()[1], image.dims()[2]);
+    let (out_height, out_width) = (height * scale.0, width * scale.1);
+
+    let mut output = Tensor::<T>::new(None, &[channels, out_height, out_width]).unwrap();
+
+    for i in 0..channels {
+        for j in 0..out_height {
+            for k in 0..out_width {
+                output.set(&[i, j, k], image.get(&[i, j / scale.0, k / scale.1]));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Rearranges a 3D tensor of shape `C x H x W` into a tensor of shape `(C / b^2) x (H x b) x (W x b)`, moving blocks
+/// of `b^2` channels into `b x b` spatial blocks. As in PyTorch's `PixelShuffle` (ONNX's `CRD` mode), output channel
+/// `c` is built from input channels `c x b^2..(c + 1) x b^2`.
+/// # Arguments
+///
+/// * `a` - Tensor.
+/// * `block_size` - The side length `b` of the spatial blocks.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::{depth_to_space, space_to_depth};
+///
+/// let x = Tensor::<i32>::new(
+///     Some(&[1, 2, 3, 4]),
+///     &[4, 1, 1],
+/// ).unwrap();
+/// let result = depth_to_space::<i32>(&x, 2).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[1, 2, 3, 4]), &[1, 2, 2]).unwrap();
+/// assert_eq!(result, expected);
+/// assert_eq!(space_to_depth::<i32>(&result, 2).unwrap(), x);
+/// ```
+pub fn depth_to_space<T: TensorType>(
+    a: &Tensor<T>,
+    block_size: usize,
+) -> Result<Tensor<T>, TensorError> {
+    let bs = block_size;
+    if (a.dims().len() != 3) || (bs == 0) || (a.dims()[0] % (bs * bs) != 0) {
+        return Err(TensorError::DimMismatch("depth_to_space".to_string()));
+    }
+    let (channels, height, width) = (a.dims()[0], a.dims()[1], a.dims()[2]);
+    let out_channels = channels / (bs * bs);
+
+    let mut output = Tensor::<T>::new(None, &[out_channels, height * bs, width * bs]).unwrap();
+    for c in 0..out_channels {
+        for h in 0..height {
+            for w in 0..width {
+                for i in 0..bs {
+                    for j in 0..bs {
+                        output.set(
+                            &[c, h * bs + i, w * bs + j],
+                            a.get(&[c * bs * bs + i * bs + j, h, w]),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// The inverse of [depth_to_space]: rearranges a 3D tensor of shape `C x H x W` into a tensor of shape
+/// `(C x b^2) x (H / b) x (W / b)`, moving each `b x b` spatial block into `b^2` channels.
 /// # Arguments
 ///
-/// * `image` - Tensor.
-/// * `padding` - Tuple of padding values in x and y directions.
+/// * `a` - Tensor.
+/// * `block_size` - The side length `b` of the spatial blocks.
 /// # Examples
 /// ```
 /// use ezkl::tensor::Tensor;
-/// use ezkl::tensor::ops::pad;
+/// use ezkl::tensor::ops::space_to_depth;
 ///
 /// let x = Tensor::<i32>::new(
-///     Some(&[5, 2, 3, 0, 4, -1, 3, 1, 6]),
-///     &[1, 3, 3],
-/// ).unwrap();
-/// let result = pad::<i32>(&x, (1, 1)).unwrap();
-/// let expected = Tensor::<i32>::new(
-///     Some(&[0, 0, 0, 0, 0, 0, 5, 2, 3, 0, 0, 0, 4, -1, 0, 0, 3, 1, 6, 0, 0, 0, 0, 0, 0]),
-///     &[1, 5, 5],
+///     Some(&[1, 2, 3, 4]),
+///     &[1, 2, 2],
 /// ).unwrap();
+/// let result = space_to_depth::<i32>(&x, 2).unwrap();
+/// let expected = Tensor::<i32>::new(Some(&[1, 2, 3, 4]), &[4, 1, 1]).unwrap();
 /// assert_eq!(result, expected);
 /// ```
-pub fn pad<T: TensorType>(
-    image: &Tensor<T>,
-    padding: (usize, usize),
+pub fn space_to_depth<T: TensorType>(
+    a: &Tensor<T>,
+    block_size: usize,
 ) -> Result<Tensor<T>, TensorError> {
-    if image.dims().len() != 3 {
-        return Err(TensorError::DimMismatch("pad".to_string()));
+    let bs = block_size;
+    if (a.dims().len() != 3) || (bs == 0) || (a.dims()[1] % bs != 0) || (a.dims()[2] % bs != 0) {
+        return Err(TensorError::DimMismatch("space_to_depth".to_string()));
     }
-    let (channels, height, width) = (image.dims()[0], image.dims()[1], image.dims()[2]);
-    let padded_height = height + 2 * padding.0;
-    let padded_width = width + 2 * padding.1;
-
-    let mut output = Tensor::<T>::new(None, &[channels, padded_height, padded_width]).unwrap();
-
-    for channel in 0..channels {
-        for row in 0..height {
-            for col in 0..width {
-                output.set(
-                    &[channel, row + padding.0, col + padding.1],
-                    image.get(&[channel, row, col]).clone(),
-                );
+    let (channels, height, width) = (a.dims()[0], a.dims()[1] / bs, a.dims()[2] / bs);
+
+    let mut output = Tensor::<T>::new(None, &[channels * bs * bs, height, width]).unwrap();
+    for c in 0..channels {
+        for h in 0..height {
+            for w in 0..width {
+                for i in 0..bs {
+                    for j in 0..bs {
+                        output.set(
+                            &[c * bs * bs + i * bs + j, h, w],
+                            a.get(&[c, h * bs + i, w * bs + j]),
+                        );
+                    }
+                }
             }
         }
     }
-
-    output.reshape(&[channels, padded_height, padded_width]);
     Ok(output)
 }
 
@@ -871,6 +2359,41 @@ pub mod nonlinearities {
         output
     }
 
+    /// Elementwise applies the exponential function to a tensor of integers, e.g. for the numerators of a softmax.
+    /// Inputs are clamped to `ln(i32::MAX / scale_output)` (at `scale_input`) such that large inputs saturate at
+    /// (close to) `i32::MAX` rather than overflowing.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// * `scale_output` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::exp;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[-8, -2, 0, 2, 4, 1000]),
+    ///     &[2, 3],
+    /// ).unwrap();
+    /// let result = exp(&x, 2, 10);
+    /// let expected = Tensor::<i32>::new(Some(&[0, 4, 10, 27, 74, 2147483647]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn exp(a: &Tensor<i32>, scale_input: usize, scale_output: usize) -> Tensor<i32> {
+        // the largest input whose (rescaled) output still fits in an i32
+        let max_kix = (i32::MAX as f64 / scale_output as f64).ln();
+        // calculate value of output
+        let mut output: Tensor<i32> = a.clone();
+
+        for (i, a_i) in a.iter().enumerate() {
+            let kix = ((*a_i as f64) / (scale_input as f64)).min(max_kix);
+            let fout = (scale_output as f64) * kix.exp();
+            let rounded = fout.round();
+            output[i] = rounded as i32;
+        }
+        output
+    }
+
     /// Elementwise applies sigmoid to a tensor of integers.
     /// # Arguments
     ///
@@ -902,6 +2425,146 @@ pub mod nonlinearities {
         output
     }
 
+    /// Elementwise squares a tensor of integers, rescaling the result by `scale` such that the output has the same
+    /// (fixed point) scale as the input. Squares in `i64` and rounds half up, so the result is exact beyond the
+    /// 24 bits an `f32` can hold; results that don't fit an `i32` saturate.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::square;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[2, 15, -3, 1, -1, 0]),
+    ///     &[2, 3],
+    /// ).unwrap();
+    /// let result = square(&x, 2);
+    /// let expected = Tensor::<i32>::new(Some(&[2, 113, 5, 1, 1, 0]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    ///
+    /// // 4097^2 = 16785409 isn't representable as an f32
+    /// let result = square(&Tensor::from([4097].into_iter()), 1);
+    /// assert_eq!(result[0], 16785409);
+    /// ```
+    pub fn square(a: &Tensor<i32>, scale: usize) -> Tensor<i32> {
+        // calculate value of output
+        let mut output: Tensor<i32> = a.clone();
+        let scale = scale as i64;
+
+        for (i, a_i) in a.iter().enumerate() {
+            let kix = (*a_i as i64) * (*a_i as i64);
+            let rounded = (kix + scale / 2) / scale;
+            output[i] = i32::try_from(rounded).unwrap_or(i32::MAX);
+        }
+        output
+    }
+
+    /// Elementwise applies mish, `x * tanh(softplus(x))` where `softplus(x) = ln(1 + e^x)`, to a tensor of integers.
+    /// Unlike relu, mish dips slightly below 0 for small negative inputs before tending back to 0.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// * `scale_output` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::mish;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[-40, -8, -4, -2, 0, 4]),
+    ///     &[2, 3],
+    /// ).unwrap();
+    /// let result = mish(&x, 4, 100);
+    /// let expected = Tensor::<i32>::new(Some(&[0, -25, -30, -22, 0, 87]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn mish(a: &Tensor<i32>, scale_input: usize, scale_output: usize) -> Tensor<i32> {
+        // calculate value of output
+        let mut output: Tensor<i32> = a.clone();
+
+        for (i, a_i) in a.iter().enumerate() {
+            let kix = (*a_i as f32) / (scale_input as f32);
+            // softplus(x) tends to x for large x, where e^x would overflow
+            let softplus = if kix > 20.0 { kix } else { kix.exp().ln_1p() };
+            let fout = (scale_output as f32) * kix * softplus.tanh();
+            let rounded = fout.round();
+            output[i] = rounded as i32;
+        }
+        output
+    }
+
+    /// Elementwise applies relu to a tensor of integers, rescaling by `scale` and mapping negative values to 0.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::relu;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[2, 15, 2, 1, -1, -5]),
+    ///     &[2, 3],
+    /// ).unwrap();
+    /// let result = relu(&x, 1);
+    /// let expected = Tensor::<i32>::new(Some(&[2, 15, 2, 1, 0, 0]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn relu(a: &Tensor<i32>, scale: usize) -> Tensor<i32> {
+        // calculate value of output
+        let mut output: Tensor<i32> = a.clone();
+
+        for (i, a_i) in a.iter().enumerate() {
+            output[i] = if a_i < &0 {
+                0
+            } else {
+                let d_inv_x = (*a_i as f32) / (scale as f32);
+                d_inv_x.round() as i32
+            };
+        }
+        output
+    }
+
+    /// Elementwise applies thresholded relu to a tensor of integers, mapping values which are not strictly greater
+    /// than `theta` to 0 and rescaling the others by `scale`.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale` - Single value
+    /// * `theta` - The threshold, in the same (quantized) units as `a`, i.e. before rescaling by `scale`.
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::thresholded_relu;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[2, 15, 4, 3, -1, -5]),
+    ///     &[2, 3],
+    /// ).unwrap();
+    /// let result = thresholded_relu(&x, 1, 3);
+    /// let expected = Tensor::<i32>::new(Some(&[0, 15, 4, 0, 0, 0]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// // theta is compared against the input before it is rescaled
+    /// let result = thresholded_relu(&x, 2, 3);
+    /// let expected = Tensor::<i32>::new(Some(&[0, 8, 2, 0, 0, 0]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn thresholded_relu(a: &Tensor<i32>, scale: usize, theta: i32) -> Tensor<i32> {
+        // calculate value of output
+        let mut output: Tensor<i32> = a.clone();
+
+        for (i, a_i) in a.iter().enumerate() {
+            output[i] = if a_i <= &theta {
+                0
+            } else {
+                let d_inv_x = (*a_i as f32) / (scale as f32);
+                d_inv_x.round() as i32
+            };
+        }
+        output
+    }
+
     /// Elementwise applies leaky relu to a tensor of integers.
     /// # Arguments
     ///
@@ -977,6 +2640,211 @@ pub mod nonlinearities {
         output
     }
 
+    /// Resizes a 3D tensor of integers of shape `C x H x W` to `C x out_h x out_w` using bilinear interpolation.
+    /// Input pixel centres are used for the sampling grid (i.e. `align_corners = false`, as in ONNX's `half_pixel` mode):
+    /// output pixel `j` samples the input at `(j + 0.5) * H / out_h - 0.5`, clamped to the image border.
+    /// As the interpolation is computed over floats this is only intended for generating witnesses.
+    /// # Arguments
+    ///
+    /// * `image` - Tensor
+    /// * `out_h` - Output height
+    /// * `out_w` - Output width
+    /// * `scale_input` - Single value
+    /// * `scale_output` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::resize_bilinear;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[0, 4, 8, 12]),
+    ///     &[1, 2, 2],
+    /// ).unwrap();
+    /// let result = resize_bilinear(&x, 4, 4, 1, 1).unwrap();
+    /// let expected = Tensor::<i32>::new(
+    ///     Some(&[0, 1, 3, 4, 2, 3, 5, 6, 6, 7, 9, 10, 8, 9, 11, 12]),
+    ///     &[1, 4, 4],
+    /// ).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn resize_bilinear(
+        image: &Tensor<i32>,
+        out_h: usize,
+        out_w: usize,
+        scale_input: usize,
+        scale_output: usize,
+    ) -> Result<Tensor<i32>, TensorError> {
+        if image.dims().len() != 3 || image.is_empty() {
+            return Err(TensorError::DimMismatch("resize_bilinear".to_string()));
+        }
+        let (channels, height, width) = (image.dims()[0], image.dims()[1], image.dims()[2]);
+
+        // maps an output coordinate to the two input coordinates it lies between, and the weight of the latter
+        let source = |dst: usize, in_len: usize, out_len: usize| -> (usize, usize, f32) {
+            let src = ((dst as f32 + 0.5) * (in_len as f32) / (out_len as f32) - 0.5).max(0.0);
+            let lo = (src.floor() as usize).min(in_len - 1);
+            let hi = (lo + 1).min(in_len - 1);
+            (lo, hi, (src - lo as f32).min(1.0))
+        };
+
+        let mut output = Tensor::<i32>::new(None, &[channels, out_h, out_w]).unwrap();
+
+        for i in 0..channels {
+            for j in 0..out_h {
+                let (y0, y1, dy) = source(j, height, out_h);
+                for k in 0..out_w {
+                    let (x0, x1, dx) = source(k, width, out_w);
+                    let top = (1.0 - dx) * (image.get(&[i, y0, x0]) as f32)
+                        + dx * (image.get(&[i, y0, x1]) as f32);
+                    let bottom = (1.0 - dx) * (image.get(&[i, y1, x0]) as f32)
+                        + dx * (image.get(&[i, y1, x1]) as f32);
+                    let kix = ((1.0 - dy) * top + dy * bottom) / (scale_input as f32);
+                    let fout = (scale_output as f32) * kix;
+                    output.set(&[i, j, k], fout.round() as i32);
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    /// Applies instance normalization to a tensor of integers of shape `C x H x W`: each channel is normalized to zero
+    /// mean and unit variance over its own `H x W` elements (with no affine parameters), computed over floats. Errors
+    /// if `a` isn't 3 dimensional.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// * `scale_output` - Single value
+    /// * `eps` - Added to the variance for numerical stability
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::instancenorm;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[1, 2, 3, 4, 10, 30, 50, 70]),
+    ///     &[2, 2, 2],
+    /// ).unwrap();
+    /// let result = instancenorm(&x, 1, 128, 1e-5).unwrap();
+    /// for c in 0..2 {
+    ///     let mean: i32 = result.get_slice(&[c..c + 1]).unwrap().iter().sum::<i32>() / 4;
+    ///     assert!(mean.abs() <= 1);
+    /// }
+    /// // both channels are an evenly spaced ramp, so normalize to the same values
+    /// assert_eq!(result.get_slice(&[0..1]).unwrap(), result.get_slice(&[1..2]).unwrap());
+    /// assert!(instancenorm(&Tensor::<i32>::new(None, &[2, 4]).unwrap(), 1, 128, 1e-5).is_err());
+    /// ```
+    pub fn instancenorm(
+        a: &Tensor<i32>,
+        scale_input: usize,
+        scale_output: usize,
+        eps: f32,
+    ) -> Result<Tensor<i32>, TensorError> {
+        if a.dims().len() != 3 {
+            return Err(TensorError::DimMismatch("instancenorm".to_string()));
+        }
+        groupnorm(a, a.dims()[0].max(1), scale_input, scale_output, eps)
+    }
+
+    /// Applies group normalization to a tensor of integers of shape `C x H x W`: the channels are split into
+    /// `num_groups` groups of consecutive channels, each of which is normalized to zero mean and unit variance over its
+    /// own elements (with no affine parameters), computed over floats. Errors if `a` isn't 3 dimensional, or if
+    /// `num_groups` is zero or doesn't divide `C`.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `num_groups` - Number of groups to split the channels into
+    /// * `scale_input` - Single value
+    /// * `scale_output` - Single value
+    /// * `eps` - Added to the variance for numerical stability
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::groupnorm;
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[1, 2, 3, 4, 10, 30, 50, 70, -5, 0, 5, 9, 2, 2, 4, 4]),
+    ///     &[4, 2, 2],
+    /// ).unwrap();
+    /// let result = groupnorm(&x, 2, 1, 128, 1e-5).unwrap();
+    /// // each group of 2 channels normalizes to a mean of (close to) zero
+    /// for g in 0..2 {
+    ///     let mean: i32 = result.get_slice(&[2 * g..2 * g + 2]).unwrap().iter().sum::<i32>() / 8;
+    ///     assert!(mean.abs() <= 1);
+    /// }
+    /// assert!(groupnorm(&x, 3, 1, 128, 1e-5).is_err());
+    /// assert!(groupnorm(&x, 0, 1, 128, 1e-5).is_err());
+    /// ```
+    pub fn groupnorm(
+        a: &Tensor<i32>,
+        num_groups: usize,
+        scale_input: usize,
+        scale_output: usize,
+        eps: f32,
+    ) -> Result<Tensor<i32>, TensorError> {
+        if a.dims().len() != 3 || num_groups == 0 || a.dims()[0] % num_groups != 0 {
+            return Err(TensorError::DimMismatch("groupnorm".to_string()));
+        }
+        // calculate value of output
+        let mut output: Tensor<i32> = a.clone();
+        let group_size = (a.len() / num_groups).max(1);
+
+        for (g, chunk) in a.chunks(group_size).enumerate() {
+            let kix: Vec<f32> = chunk
+                .iter()
+                .map(|a_i| (*a_i as f32) / (scale_input as f32))
+                .collect();
+            let mean = kix.iter().sum::<f32>() / (kix.len() as f32);
+            let var = kix.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / (kix.len() as f32);
+            for (i, x) in kix.iter().enumerate() {
+                let fout = (scale_output as f32) * (x - mean) / (var + eps).sqrt();
+                output[g * group_size + i] = fout.round() as i32;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Computes the cosine similarity `dot(a, b) / (||a|| ||b||)` of two tensors of integers, returned at
+    /// `scale_output`. Both inputs share `scale_input`, which cancels out of the ratio. If either input has a zero norm
+    /// the similarity is undefined and 0 is returned.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `b` - Tensor
+    /// * `scale_input` - Single value
+    /// * `scale_output` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::cosine_similarity;
+    /// let x = Tensor::<i32>::new(Some(&[3, 4]), &[2]).unwrap();
+    /// let y = Tensor::<i32>::new(Some(&[4, 3]), &[2]).unwrap();
+    /// assert_eq!(cosine_similarity(&x, &y, 1, 100).unwrap(), 96);
+    /// assert_eq!(cosine_similarity(&x, &x, 1, 100).unwrap(), 100);
+    /// assert_eq!(cosine_similarity(&x, &-x.clone(), 1, 100).unwrap(), -100);
+    /// let orthogonal = Tensor::<i32>::new(Some(&[-4, 3]), &[2]).unwrap();
+    /// assert_eq!(cosine_similarity(&x, &orthogonal, 1, 100).unwrap(), 0);
+    /// // inputs must have the same number of elements
+    /// assert!(cosine_similarity(&x, &Tensor::<i32>::new(Some(&[1]), &[1]).unwrap(), 1, 100).is_err());
+    /// ```
+    pub fn cosine_similarity(
+        a: &Tensor<i32>,
+        b: &Tensor<i32>,
+        scale_input: usize,
+        scale_output: usize,
+    ) -> Result<i32, TensorError> {
+        if a.len() != b.len() {
+            return Err(TensorError::DimMismatch("cosine_similarity".to_string()));
+        }
+        // dequantize into f64s such that the products can't overflow
+        let dequantize = |t: &Tensor<i32>| t.map(|e| e as f64 / scale_input as f64);
+        let (a, b) = (dequantize(a), dequantize(b));
+        let norm = |t: &Tensor<f64>| dot(&vec![t, t]).map(|d| d[0].sqrt());
+        let denom = norm(&a)? * norm(&b)?;
+        if denom == 0.0 {
+            return Ok(0);
+        }
+        let similarity = dot(&vec![&a, &b])?[0] / denom;
+        Ok((scale_output as f64 * similarity).round() as i32)
+    }
+
     /// Elementwise divides a tensor with a const integer element.
     /// # Arguments
     ///
@@ -1006,4 +2874,189 @@ pub mod nonlinearities {
         }
         output
     }
+
+    /// Applies 2D average pooling over a 3D tensor of integers of shape C x H x W, rounding to the nearest integer.
+    /// If `count_include_pad` is true each window is divided by the full kernel size, otherwise only by the number of
+    /// non-padding elements it covers (as in ONNX / PyTorch). Computed using [sumpool].
+    /// # Arguments
+    ///
+    /// * `image` - Tensor.
+    /// * `padding` - Tuple of padding values in x and y directions.
+    /// * `stride` - Tuple of stride values in x and y directions.
+    /// * `kernel_shape` - Tuple of pooling window size in x and y directions.
+    /// * `count_include_pad` - Whether padding elements count towards each window's size.
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::avg_pool2d;
+    ///
+    /// let x = Tensor::<i32>::new(
+    ///     Some(&[4, 8, 12, 16]),
+    ///     &[1, 2, 2],
+    /// ).unwrap();
+    /// // the corner windows only cover a single real element, the edge windows two.
+    /// let pooled = avg_pool2d(&x, (1, 1), (1, 1), (2, 2), true).unwrap();
+    /// let expected = Tensor::<i32>::new(Some(&[1, 3, 2, 4, 10, 6, 3, 7, 4]), &[1, 3, 3]).unwrap();
+    /// assert_eq!(pooled, expected);
+    ///
+    /// let pooled = avg_pool2d(&x, (1, 1), (1, 1), (2, 2), false).unwrap();
+    /// let expected = Tensor::<i32>::new(Some(&[4, 6, 8, 8, 10, 12, 12, 14, 16]), &[1, 3, 3]).unwrap();
+    /// assert_eq!(pooled, expected);
+    /// ```
+    pub fn avg_pool2d(
+        image: &Tensor<i32>,
+        padding: (usize, usize),
+        stride: (usize, usize),
+        kernel_shape: (usize, usize),
+        count_include_pad: bool,
+    ) -> Result<Tensor<i32>, TensorError> {
+        let sums = sumpool(image, padding, stride, kernel_shape)?;
+        let counts = if count_include_pad {
+            sums.map(|_| (kernel_shape.0 * kernel_shape.1) as i32)
+        } else {
+            // pooling over ones counts the real elements in each window
+            sumpool(&image.map(|_| 1), padding, stride, kernel_shape)?
+        };
+
+        let mut output: Tensor<i32> = sums.clone();
+        for i in 0..output.len() {
+            let d_inv_x = (sums[i] as f32) / (counts[i] as f32);
+            output[i] = d_inv_x.round() as i32;
+        }
+        Ok(output)
+    }
+
+    /// Matrix multiplies two quantized tensors and requantizes the result to `out_scale`, rounding to the nearest
+    /// integer. Scales are fixed point multipliers, i.e. an integer `x` with scale `s` represents `x / s`, such that
+    /// the integer product has scale `s_a * s_b`.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `s_a` - Scale of `a`
+    /// * `b` - Tensor
+    /// * `s_b` - Scale of `b`
+    /// * `out_scale` - Scale of the output
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::quantized_matmul;
+    /// // a = [[0.5, 1.0], [1.5, -0.5]] and b = [[1.0], [0.25]]
+    /// let a = Tensor::<i32>::new(Some(&[2, 4, 6, -2]), &[2, 2]).unwrap();
+    /// let b = Tensor::<i32>::new(Some(&[8, 2]), &[2, 1]).unwrap();
+    /// let result = quantized_matmul(&a, 4.0, &b, 8.0, 16.0).unwrap();
+    /// // float reference: a x b = [[0.75], [1.375]]
+    /// let reference = [0.5 * 1.0 + 1.0 * 0.25, 1.5 * 1.0 - 0.5 * 0.25];
+    /// let expected: Vec<i32> = reference.iter().map(|x: &f32| (x * 16.0).round() as i32).collect();
+    /// assert_eq!(result, Tensor::<i32>::new(Some(&expected), &[2, 1]).unwrap());
+    /// ```
+    pub fn quantized_matmul(
+        a: &Tensor<i32>,
+        s_a: f32,
+        b: &Tensor<i32>,
+        s_b: f32,
+        out_scale: f32,
+    ) -> Result<Tensor<i32>, TensorError> {
+        let product = matmul(&vec![a.clone(), b.clone()])?;
+        let multiplier = out_scale / (s_a * s_b);
+
+        let mut output: Tensor<i32> = product.clone();
+        for i in 0..output.len() {
+            output[i] = ((product[i] as f32) * multiplier).round() as i32;
+        }
+        Ok(output)
+    }
+}
+
+/// An op that can be applied to witness (integer) tensors by value rather than by calling the corresponding function
+/// directly, for instance when interpreting a serialized graph. This is the witness-side mirror of the circuit's
+/// polynomial and lookup ops.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TensorOp {
+    Add,
+    Sub,
+    Mult,
+    Sum,
+    Dot,
+    Matmul,
+    Affine,
+    Conv {
+        padding: (usize, usize),
+        stride: (usize, usize),
+    },
+    SumPool {
+        padding: (usize, usize),
+        stride: (usize, usize),
+        kernel_shape: (usize, usize),
+    },
+    ReLU {
+        scale: usize,
+    },
+    Sigmoid {
+        scales: (usize, usize),
+    },
+    Div {
+        scale: usize,
+    },
+}
+
+impl TensorOp {
+    /// Applies the op to `inputs`, which are laid out as for the corresponding function in [crate::tensor::ops].
+    /// # Arguments
+    ///
+    /// * `inputs` - Slice of tensors the op is applied to.
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::TensorOp;
+    ///
+    /// let x = Tensor::<i32>::new(Some(&[1, -2]), &[2]).unwrap();
+    /// let k = Tensor::<i32>::new(Some(&[2, 1, -1, 3]), &[2, 2]).unwrap();
+    /// let b = Tensor::<i32>::new(Some(&[0, 4]), &[2]).unwrap();
+    ///
+    /// let x = TensorOp::Affine.apply(&[x, k, b]).unwrap();
+    /// assert_eq!(x, Tensor::<i32>::new(Some(&[0, -3]), &[2, 1]).unwrap());
+    /// let x = TensorOp::ReLU { scale: 1 }.apply(&[x]).unwrap();
+    /// assert_eq!(x, Tensor::<i32>::new(Some(&[0, 0]), &[2, 1]).unwrap());
+    /// let y = Tensor::<i32>::new(Some(&[3, 5]), &[2, 1]).unwrap();
+    /// let x = TensorOp::Add.apply(&[x, y]).unwrap();
+    /// let x = TensorOp::Sum.apply(&[x]).unwrap();
+    /// assert_eq!(x, Tensor::<i32>::new(Some(&[8]), &[1]).unwrap());
+    ///
+    /// // the number of inputs is checked against the op
+    /// assert!(TensorOp::Sum.apply(&[]).is_err());
+    /// ```
+    pub fn apply(&self, inputs: &[Tensor<i32>]) -> Result<Tensor<i32>, TensorError> {
+        let valid = match self {
+            TensorOp::Add | TensorOp::Sub | TensorOp::Mult => !inputs.is_empty(),
+            TensorOp::Dot | TensorOp::Matmul => inputs.len() == 2,
+            TensorOp::Affine => inputs.len() == 3,
+            TensorOp::Conv { .. } => inputs.len() == 2 || inputs.len() == 3,
+            _ => inputs.len() == 1,
+        };
+        if !valid {
+            return Err(TensorError::DimMismatch(format!("{:?}", self)));
+        }
+
+        match self {
+            TensorOp::Add => add(&inputs.to_vec()),
+            TensorOp::Sub => sub(&inputs.to_vec()),
+            TensorOp::Mult => mult(&inputs.to_vec()),
+            TensorOp::Sum => sum(&inputs[0]),
+            TensorOp::Dot => dot(&inputs.iter().collect()),
+            TensorOp::Matmul => matmul(&inputs.to_vec()),
+            TensorOp::Affine => affine(&inputs.to_vec()),
+            TensorOp::Conv { padding, stride } => convolution(&inputs.to_vec(), *padding, *stride),
+            TensorOp::SumPool {
+                padding,
+                stride,
+                kernel_shape,
+            } => sumpool(&inputs[0], *padding, *stride, *kernel_shape),
+            TensorOp::ReLU { scale } => Ok(nonlinearities::relu(&inputs[0], *scale)),
+            TensorOp::Sigmoid { scales } => {
+                Ok(nonlinearities::sigmoid(&inputs[0], scales.0, scales.1))
+            }
+            TensorOp::Div { scale } => Ok(nonlinearities::const_div(&inputs[0], *scale as i32)),
+        }
+    }
 }
@@ -62,6 +62,19 @@ impl<F: FieldExt + TensorType> From<Tensor<AssignedCell<F, F>>> for ValTensor<F>
     }
 }
 
+impl<F: FieldExt + TensorType> Tensor<Value<F>> {
+    /// Wraps each element of `t` in [Value::known].
+    pub fn known_from(t: &Tensor<F>) -> Tensor<Value<F>> {
+        t.map(Value::known)
+    }
+
+    /// Converts a tensor of known (or unknown) values into a [ValTensor::Value], such that
+    /// `Tensor::known_from(&t).map_known()` replaces the `t.map(Value::known).into()` pattern.
+    pub fn map_known(self) -> ValTensor<F> {
+        self.into()
+    }
+}
+
 impl<F: FieldExt + TensorType> ValTensor<F> {
     /// Allocate a new [ValTensor::Instance] from the ConstraintSystem with the given tensor `dims`, optionally enabling `equality`.
     pub fn new_instance(cs: &mut ConstraintSystem<F>, dims: Vec<usize>, equality: bool) -> Self {
@@ -72,6 +85,15 @@ impl<F: FieldExt + TensorType> ValTensor<F> {
         ValTensor::Instance { inner: col, dims }
     }
 
+    /// Creates a [ValTensor::Value] of shape `dims` where every element is [Value::unknown()], for use in keygen-time
+    /// circuits (see `Circuit::without_witnesses`), such that no witness data ends up in the keys.
+    pub fn unknown(dims: &[usize]) -> ValTensor<F> {
+        let mut t: Tensor<Value<F>> =
+            Tensor::from((0..dims.iter().product::<usize>()).map(|_| Value::unknown()));
+        t.reshape(dims);
+        t.into()
+    }
+
     /// Calls `get_slice` on the inner tensor.
     pub fn get_slice(&self, indices: &[Range<usize>]) -> Result<ValTensor<F>, Box<dyn Error>> {
         let slice = match self {
@@ -101,8 +123,12 @@ impl<F: FieldExt + TensorType> ValTensor<F> {
         Ok(slice)
     }
 
-    /// Sets the [ValTensor]'s shape.
+    /// Sets the [ValTensor]'s shape. Errors if `new_dims` doesn't hold the same number of elements, in which case the
+    /// [ValTensor] is left untouched. Assigned cells (and so their equality constraints) are preserved.
     pub fn reshape(&mut self, new_dims: &[usize]) -> Result<(), Box<dyn Error>> {
+        if self.dims().iter().product::<usize>() != new_dims.iter().product::<usize>() {
+            return Err(Box::new(TensorError::DimError));
+        }
         match self {
             ValTensor::Value { inner: v, dims: d } => {
                 v.reshape(new_dims);
@@ -117,9 +143,6 @@ impl<F: FieldExt + TensorType> ValTensor<F> {
                 *d = v.dims().to_vec();
             }
             ValTensor::Instance { dims: d, .. } => {
-                if d.iter().product::<usize>() != new_dims.iter().product::<usize>() {
-                    return Err(Box::new(TensorError::DimError));
-                }
                 *d = new_dims.to_vec();
             }
         };
@@ -147,6 +170,29 @@ impl<F: FieldExt + TensorType> ValTensor<F> {
         }
     }
 
+    /// Flattens all dimensions but the first (batch) one, such that a `[N, C, H, W]` [ValTensor] becomes `[N, C*H*W]`.
+    /// Leaves [ValTensor]s of rank 2 or less unchanged.
+    pub fn flatten_except_batch(&mut self) {
+        if self.dims().len() > 2 {
+            let dims = [self.dims()[0], self.dims()[1..].iter().product()];
+            // the number of elements is unchanged so this can't fail
+            self.reshape(&dims).unwrap();
+        }
+    }
+
+    /// Applies `g` to each [Value] of a [ValTensor::Value], e.g. to rescale inputs between layers, keeping the
+    /// variant and shape. Assigned and [ValTensor::Instance] variants are returned unchanged, as their values are
+    /// already fixed in the circuit.
+    pub fn map<G: Fn(Value<F>) -> Value<F>>(&self, g: G) -> ValTensor<F> {
+        match self {
+            ValTensor::Value { inner, dims } => ValTensor::Value {
+                inner: inner.map(g),
+                dims: dims.clone(),
+            },
+            _ => self.clone(),
+        }
+    }
+
     /// Returns the `dims` attribute of the [ValTensor].
     pub fn dims(&self) -> &[usize] {
         match self {
@@ -156,6 +202,17 @@ impl<F: FieldExt + TensorType> ValTensor<F> {
             | ValTensor::Instance { dims: d, .. } => d,
         }
     }
+
+    /// Returns the number of elements in the [ValTensor], for any variant.
+    pub fn len(&self) -> usize {
+        self.dims().iter().product()
+    }
+
+    /// Checks if the [ValTensor] has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// A [String] representation of the [ValTensor] for display, for example in showing intermediate values in a computational graph.
     pub fn show(&self) -> String {
         match self.clone() {
@@ -167,3 +224,187 @@ impl<F: FieldExt + TensorType> ValTensor<F> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::Circuit,
+    };
+    use halo2curves::pasta::Fp;
+
+    const K: usize = 4;
+
+    fn values(v: &[u64]) -> ValTensor<Fp> {
+        let t: Tensor<Value<Fp>> = Tensor::from(v.iter().map(|e| Value::known(Fp::from(*e))));
+        t.into()
+    }
+
+    #[test]
+    fn known_values_build_val_tensor() {
+        let t = Tensor::new(Some(&[1_u64, 2, 3, 4].map(Fp::from)), &[2, 2]).unwrap();
+        let known = Tensor::known_from(&t);
+        assert_eq!(known.dims(), &[2, 2]);
+
+        match known.map_known() {
+            ValTensor::Value { inner, dims } => {
+                assert_eq!(dims, vec![2, 2]);
+                for (v, e) in inner.iter().zip(t.iter()) {
+                    v.map(|v| assert_eq!(v, *e));
+                }
+            }
+            _ => panic!("known values should build a ValTensor::Value"),
+        }
+    }
+
+    #[test]
+    fn map_doubles_values() {
+        let mut t = values(&[1, 2, 3, 4]);
+        t.reshape(&[2, 2]).unwrap();
+        match t.map(|v| v + v) {
+            ValTensor::Value { inner, dims } => {
+                assert_eq!(dims, vec![2, 2]);
+                for (v, e) in inner.iter().zip([2_u64, 4, 6, 8]) {
+                    v.map(|v| assert_eq!(v, Fp::from(e)));
+                }
+            }
+            _ => panic!("map should preserve the variant"),
+        }
+
+        // instances aren't witnessed so are left untouched
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let t = ValTensor::new_instance(&mut cs, vec![2, 2], true);
+        assert!(matches!(t.map(|v| v + v), ValTensor::Instance { .. }));
+    }
+
+    #[test]
+    fn reshape_value() {
+        let mut t = values(&[1, 2, 3, 4, 5, 6]);
+        t.reshape(&[2, 3]).unwrap();
+        assert_eq!(t.dims(), &[2, 3]);
+        match &t {
+            ValTensor::Value { inner, .. } => assert_eq!(inner.dims(), &[2, 3]),
+            _ => panic!("reshape should preserve the variant"),
+        }
+        assert!(t.reshape(&[4]).is_err());
+        assert_eq!(t.dims(), &[2, 3]);
+    }
+
+    #[test]
+    fn reshape_instance() {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let mut t = ValTensor::new_instance(&mut cs, vec![6], true);
+        t.reshape(&[3, 2]).unwrap();
+        assert_eq!(t.dims(), &[3, 2]);
+        assert!(t.reshape(&[7]).is_err());
+        assert_eq!(t.dims(), &[3, 2]);
+    }
+
+    #[test]
+    fn len_across_variants() {
+        let t = values(&[1, 2, 3, 4, 5, 6]);
+        assert_eq!((t.dims(), t.len()), (&[6][..], 6));
+
+        let assigned: Tensor<Value<Assigned<Fp>>> =
+            Tensor::new(Some(&[Value::known(Fp::from(1_u64).into()); 4]), &[2, 2]).unwrap();
+        let t: ValTensor<Fp> = assigned.into();
+        assert_eq!((t.dims(), t.len()), (&[2, 2][..], 4));
+
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let t = ValTensor::new_instance(&mut cs, vec![3, 2, 2], true);
+        assert_eq!((t.dims(), t.len()), (&[3, 2, 2][..], 12));
+        assert!(!t.is_empty());
+
+        assert!(ValTensor::<Fp>::unknown(&[0, 3]).is_empty());
+    }
+
+    #[test]
+    fn flatten_except_batch() {
+        let mut t = values(&(0..24).collect::<Vec<_>>());
+        t.reshape(&[2, 3, 2, 2]).unwrap();
+        t.flatten_except_batch();
+        assert_eq!(t.dims(), &[2, 12]);
+        match &t {
+            ValTensor::Value { inner, .. } => {
+                assert_eq!(inner.dims(), &[2, 12]);
+                let _ = inner[13].map(|v| assert_eq!(v, Fp::from(13_u64)));
+            }
+            _ => panic!("flattening should preserve the variant"),
+        }
+
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let mut t = ValTensor::new_instance(&mut cs, vec![4, 1, 3, 3], true);
+        t.flatten_except_batch();
+        assert_eq!(t.dims(), &[4, 9]);
+
+        // already flat per sample
+        let mut t = values(&[1, 2, 3]);
+        t.flatten_except_batch();
+        assert_eq!(t.dims(), &[3]);
+    }
+
+    #[derive(Clone)]
+    struct ReshapeCircuit {
+        input: ValTensor<Fp>,
+    }
+
+    impl Circuit<Fp> for ReshapeCircuit {
+        type Config = (VarTensor, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = VarTensor::new_advice(cs, K, 4, vec![4], true, 512);
+            let instance = cs.instance_column();
+            cs.enable_equality(instance);
+            (advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), halo2_proofs::plonk::Error> {
+            let cells = layouter.assign_region(
+                || "input",
+                |mut region| config.0.assign(&mut region, 0, &self.input),
+            )?;
+            let mut t: ValTensor<Fp> = cells.into();
+            t.reshape(&[2, 2])
+                .map_err(|_| halo2_proofs::plonk::Error::Synthesis)?;
+            assert!(t.reshape(&[3]).is_err());
+            assert_eq!(t.dims(), &[2, 2]);
+            assert_eq!(t.len(), 4);
+            // the reshaped cells must still be the assigned ones, so we can constrain them to the instance.
+            match t {
+                ValTensor::PrevAssigned { inner, .. } => {
+                    assert_eq!(inner.dims(), &[2, 2]);
+                    for (i, cell) in inner.iter().enumerate() {
+                        layouter.constrain_instance(cell.cell(), config.1, i)?;
+                    }
+                }
+                _ => panic!("reshape should preserve the variant"),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reshape_prev_assigned() {
+        let circuit = ReshapeCircuit {
+            input: values(&[1, 2, 3, 4]),
+        };
+        let instance: Vec<Fp> = [1_u64, 2, 3, 4].iter().map(|e| Fp::from(*e)).collect();
+        let prover = MockProver::run(K as u32, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+
+        let wrong: Vec<Fp> = [1_u64, 2, 4, 3].iter().map(|e| Fp::from(*e)).collect();
+        let prover = MockProver::run(K as u32, &circuit, vec![wrong]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
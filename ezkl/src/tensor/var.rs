@@ -1,4 +1,6 @@
 use super::*;
+use crate::circuit::CircuitError;
+use log::error;
 use std::cmp::min;
 /// A wrapper around Halo2's `Column<Fixed>` or `Column<Advice>`.
 /// The wrapper allows for `VarTensor`'s dimensions to differ from that of the inner (wrapped) columns.
@@ -113,6 +115,15 @@ impl VarTensor {
         }
     }
 
+    /// Returns the number of rows spanned when assigning a tensor of the VarTensor's dims from offset 0.
+    pub fn num_rows(&self) -> usize {
+        match self {
+            VarTensor::Advice { col_size, dims, .. } | VarTensor::Fixed { col_size, dims, .. } => {
+                min(dims.iter().product(), *col_size)
+            }
+        }
+    }
+
     /// Gets the dims of the object the VarTensor represents
     pub fn num_cols(&self) -> usize {
         match self {
@@ -173,6 +184,23 @@ impl VarTensor {
             VarTensor::Advice { capacity, .. } | VarTensor::Fixed { capacity, .. } => *capacity,
         }
     }
+
+    /// Checks that `len` elements assigned starting at `offset` fit within the (possibly larger than `capacity`) space
+    /// provided by the inner columns.
+    pub fn check_capacity(&self, offset: usize, len: usize) -> Result<(), CircuitError> {
+        let available = match self {
+            VarTensor::Advice {
+                inner, col_size, ..
+            } => inner.len() * col_size,
+            VarTensor::Fixed {
+                inner, col_size, ..
+            } => inner.len() * col_size,
+        };
+        if offset + len > available {
+            return Err(CircuitError::CapacityExceeded(offset + len, available));
+        }
+        Ok(())
+    }
 }
 
 impl VarTensor {
@@ -216,6 +244,23 @@ impl VarTensor {
         }
     }
 
+    /// Assigns constant values to the columns of a [VarTensor::Fixed], such that they are baked into the circuit (and
+    /// its keys) rather than being part of the witness. Errors when called on a [VarTensor::Advice].
+    pub fn assign_constant<F: FieldExt + TensorType>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        values: &Tensor<F>,
+    ) -> Result<Tensor<AssignedCell<F, F>>, halo2_proofs::plonk::Error> {
+        match &self {
+            VarTensor::Fixed { inner: fixed, .. } => values.enum_map(|coord, k| {
+                let (x, y) = self.cartesian_coord(offset + coord);
+                region.assign_fixed(|| "constant", fixed[x], y, || Value::known(k))
+            }),
+            _ => Err(halo2_proofs::plonk::Error::Synthesis),
+        }
+    }
+
     /// Assigns specific values (`ValTensor`) to the columns of the inner tensor.
     pub fn assign<F: FieldExt + TensorType>(
         &self,
@@ -223,6 +268,12 @@ impl VarTensor {
         offset: usize,
         values: &ValTensor<F>,
     ) -> Result<Tensor<AssignedCell<F, F>>, halo2_proofs::plonk::Error> {
+        // halo2 regions can only surface their own error type, so we log the typed error
+        self.check_capacity(offset, values.dims().iter().product())
+            .map_err(|e| {
+                error!("{}", e);
+                halo2_proofs::plonk::Error::Synthesis
+            })?;
         match values {
             ValTensor::Instance {
                 inner: instance, ..
@@ -277,3 +328,150 @@ impl VarTensor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::polynomial::{Config as PolyConfig, InputType, Node as PolyNode, Op};
+    use crate::tensor::ops::affine;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::Circuit,
+    };
+    use halo2curves::pasta::Fp;
+
+    const K: usize = 4;
+    const LEN: usize = 2;
+
+    fn kernel() -> Tensor<Fp> {
+        Tensor::new(Some(&[3_u64, 1, 2, 4].map(Fp::from)), &[LEN, LEN]).unwrap()
+    }
+
+    fn bias() -> Tensor<Fp> {
+        Tensor::new(Some(&[0_u64, 5].map(Fp::from)), &[LEN]).unwrap()
+    }
+
+    fn known(t: &Tensor<Fp>) -> ValTensor<Fp> {
+        Tensor::known_from(t).map_known()
+    }
+
+    #[derive(Clone)]
+    struct ConstantKernelCircuit {
+        input: Tensor<Fp>,
+        output: Tensor<Fp>,
+    }
+
+    impl Circuit<Fp> for ConstantKernelCircuit {
+        type Config = PolyConfig<Fp>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let input = VarTensor::new_advice(cs, K, LEN, vec![LEN], true, 512);
+            let kernel = VarTensor::new_fixed(cs, K, LEN * LEN, vec![LEN, LEN], true, 512);
+            let bias = VarTensor::new_advice(cs, K, LEN, vec![LEN], true, 512);
+            let output = VarTensor::new_advice(cs, K, LEN, vec![LEN], true, 512);
+            let affine_node = PolyNode {
+                op: Op::Affine,
+                input_order: vec![
+                    InputType::Input(0),
+                    InputType::Input(1),
+                    InputType::Input(2),
+                ],
+            };
+            PolyConfig::configure(cs, &[input, kernel, bias], &output, &[affine_node])
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), halo2_proofs::plonk::Error> {
+            layouter.assign_region(
+                || "affine with a constant kernel",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    config.inputs[0].assign(&mut region, 0, &known(&self.input))?;
+                    config.inputs[1].assign_constant(&mut region, 0, &kernel())?;
+                    config.inputs[2].assign(&mut region, 0, &known(&bias()))?;
+                    config.output.assign(&mut region, 0, &known(&self.output))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[derive(Clone)]
+    struct OversizedCircuit {
+        input: Tensor<Fp>,
+    }
+
+    impl Circuit<Fp> for OversizedCircuit {
+        type Config = VarTensor;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fp>) -> Self::Config {
+            VarTensor::new_advice(cs, K, LEN, vec![LEN], true, 512)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), halo2_proofs::plonk::Error> {
+            layouter.assign_region(
+                || "oversized",
+                |mut region| {
+                    config.assign(&mut region, 0, &known(&self.input))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn assigning_oversized_tensor_errors() {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let var = VarTensor::new_advice(&mut cs, K, LEN, vec![LEN], true, 512);
+        assert!(var.check_capacity(0, LEN).is_ok());
+        assert!(matches!(
+            var.check_capacity(0, 1 << K),
+            Err(CircuitError::CapacityExceeded(_, _))
+        ));
+
+        let circuit = OversizedCircuit {
+            input: Tensor::new(None, &[1 << K]).unwrap(),
+        };
+        assert!(MockProver::run(K as u32, &circuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn affine_with_constant_kernel() {
+        let input = Tensor::new(Some(&[1_u64, 2].map(Fp::from)), &[LEN]).unwrap();
+        let output = affine(&vec![input.clone(), kernel(), bias()]).unwrap();
+
+        let circuit = ConstantKernelCircuit {
+            input: input.clone(),
+            output: output.clone(),
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let mut wrong = output;
+        wrong[0] += Fp::from(1_u64);
+        let circuit = ConstantKernelCircuit {
+            input,
+            output: wrong,
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! Counts heap allocations to check that taking windows of a tensor with `view_slice` and `dot_views` doesn't copy
+//! each window the way `get_slice` and `dot` do. This lives in its own test binary as it swaps the global allocator.
+use ezkl::tensor::ops::{dot, dot_views};
+use ezkl::tensor::Tensor;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    // counted per thread, such that tests running in parallel don't see each other's allocations
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the output of `f` alongside the number of allocations made on this thread while running it.
+fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let res = f();
+    (res, ALLOCATIONS.with(Cell::get) - before)
+}
+
+/// Counts the allocations made taking the top left `size x size` window of `image` and dotting it with a kernel.
+fn window_allocations(image: &Tensor<i32>, size: usize, views: bool) -> (Tensor<i32>, usize) {
+    let kernel = Tensor::<i32>::new(Some(&vec![1; size * size]), &[1, size, size]).unwrap();
+    let window = [0..1, 0..size, 0..size];
+    if views {
+        count_allocations(|| {
+            dot_views(
+                &image.view_slice(&window).unwrap(),
+                &kernel.view_slice(&[]).unwrap(),
+            )
+            .unwrap()
+        })
+    } else {
+        count_allocations(|| {
+            let slice = image.get_slice(&window).unwrap();
+            dot(&vec![&slice, &kernel]).unwrap()
+        })
+    }
+}
+
+#[test]
+fn views_allocate_less_than_slices() {
+    let image = Tensor::<i32>::new(Some(&(0..256).collect::<Vec<_>>()), &[1, 16, 16]).unwrap();
+    let (copied, copy_allocations) = window_allocations(&image, 8, false);
+    let (viewed, view_allocations) = window_allocations(&image, 8, true);
+    assert_eq!(copied, viewed);
+    assert!(
+        view_allocations < copy_allocations,
+        "views made {} allocations, slices {}",
+        view_allocations,
+        copy_allocations
+    );
+}
+
+#[test]
+fn view_allocations_dont_grow_with_the_window() {
+    let image = Tensor::<i32>::new(Some(&(0..256).collect::<Vec<_>>()), &[1, 16, 16]).unwrap();
+    let (_, small) = window_allocations(&image, 2, true);
+    let (_, large) = window_allocations(&image, 16, true);
+    assert_eq!(small, large);
+}